@@ -35,9 +35,21 @@ use system::ensure_signed;
 use runtime_support::{StorageMap};
 use runtime_support::dispatch::Result;
 
+/// Notified whenever an account changes its delegate, so that modules
+/// counting delegated weight can move it to the account's new delegate
+pub trait OnDelegationChange<AccountId> {
+	fn on_delegation_change(who: &AccountId);
+}
+
+impl<AccountId> OnDelegationChange<AccountId> for () {
+	fn on_delegation_change(_who: &AccountId) {}
+}
+
 pub trait Trait: system::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Handler for changes to an account's delegate.
+	type OnDelegationChange: OnDelegationChange<Self::AccountId>;
 }
 
 decl_module! {
@@ -51,15 +63,22 @@ decl_module! {
 			let _sender = ensure_signed(origin)?;
 			// Check that no delegation cycle exists and that the depth is valid
 			ensure!(!Self::is_invalid_delegation(&_sender, to.clone(), 1), "Invalid delegation");
+			// Remove the backlink from the sender's previous delegate, if any
+			if let Some(from) = Self::delegate_of(&_sender) {
+				Self::remove_backlink(&_sender, from);
+			}
 			// Update the delegate of _sender -> Some(to)
 			<DelegatesOf<T>>::insert(&_sender, &to);
 			// Update the delegates of to to include _sender
 			if let Some(mut delegates) = <DelegatesTo<T>>::get(to.clone()) {
-				delegates.push(_sender.clone());
-				<DelegatesTo<T>>::insert(to.clone(), delegates);
+				if !delegates.contains(&_sender) {
+					delegates.push(_sender.clone());
+					<DelegatesTo<T>>::insert(to.clone(), delegates);
+				}
 			} else {
 				<DelegatesTo<T>>::insert(to.clone(), vec![_sender.clone()]);
 			}
+			T::OnDelegationChange::on_delegation_change(&_sender);
 			
 			// Fire delegation event
 			Self::deposit_event(RawEvent::Delegated(_sender, to));
@@ -77,16 +96,8 @@ decl_module! {
 			// Update the delegate to the sender, None type throws an error due to missing Trait bound
 			<DelegatesOf<T>>::remove(&_sender);
 			// Update the delegates to remove _sender (i.e. remove backlink)
-			if let Some(mut delegates) = <DelegatesTo<T>>::get(from.clone()) {
-				let index = delegates.iter().position(|d| d == &_sender.clone()).unwrap();
-				delegates.remove(index);
-
-				if delegates.len() == 0 {
-					<DelegatesTo<T>>::remove(from.clone());
-				} else {
-					<DelegatesTo<T>>::insert(from.clone(), delegates);	
-				}
-			}
+			Self::remove_backlink(&_sender, from.clone());
+			T::OnDelegationChange::on_delegation_change(&_sender);
 			// Fire delegation event
 			Self::deposit_event(RawEvent::Undelegated(_sender, from));
 
@@ -96,6 +107,18 @@ decl_module! {
 }
 
 impl<T: Trait> Module<T> {
+	/// Removes `delegator` from the accounts delegating to `delegate`
+	fn remove_backlink(delegator: &T::AccountId, delegate: T::AccountId) {
+		if let Some(mut delegates) = <DelegatesTo<T>>::get(delegate.clone()) {
+			delegates.retain(|d| d != delegator);
+			if delegates.len() == 0 {
+				<DelegatesTo<T>>::remove(delegate);
+			} else {
+				<DelegatesTo<T>>::insert(delegate, delegates);
+			}
+		}
+	}
+
 	/// Implement rudimentary DFS to find if "to"'s delegation ever leads to "from"
 	pub fn is_invalid_delegation(from: &T::AccountId, to: T::AccountId, length: u32) -> bool {
 		// If length is greater than allowed depth, reject
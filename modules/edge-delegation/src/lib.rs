@@ -41,7 +41,7 @@ extern crate srml_system as system;
 
 
 pub mod delegation;
-pub use delegation::{Module, Trait, RawEvent, Event};
+pub use delegation::{Module, Trait, RawEvent, Event, OnDelegationChange};
 
 // Tests for Delegation Module
 #[cfg(test)]
@@ -95,6 +95,7 @@ mod tests {
 
 	impl Trait for Test {
 		type Event = Event;
+		type OnDelegationChange = ();
 	}
 
 	pub type System = system::Module<Test>;
@@ -237,6 +238,22 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn redelegate_should_move_backlink() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(delegate_to(1_u64, 2_u64));
+			assert_ok!(delegate_to(1_u64, 3_u64));
+			assert_eq!(Delegation::delegates_to(2_u64), None);
+			assert_eq!(Delegation::delegates_to(3_u64), Some(vec![1_u64]));
+			assert_ok!(delegate_to(1_u64, 2_u64));
+			assert_ok!(delegate_to(1_u64, 2_u64));
+			assert_eq!(Delegation::delegates_to(2_u64), Some(vec![1_u64]));
+			assert_eq!(Delegation::delegates_to(3_u64), None);
+			assert_eq!(Delegation::delegate_of(1_u64), Some(2_u64));
+		});
+	}
+
 	#[test]
 	fn undelegate_from_oneself_should_not_work() {
 		with_externalities(&mut new_test_ext(), || {
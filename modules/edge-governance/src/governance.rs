@@ -30,17 +30,21 @@ extern crate srml_support as runtime_support;
 extern crate sr_primitives as runtime_primitives;
 extern crate sr_io as runtime_io;
 extern crate srml_system as system;
+extern crate srml_consensus as consensus;
 extern crate edge_voting as voting;
 
 use rstd::prelude::*;
 use srml_support::traits::{Currency, ReservableCurrency};
+use rstd::{cmp, result};
 use system::ensure_signed;
 use runtime_support::{StorageValue, StorageMap};
-use runtime_support::dispatch::Result;
-use runtime_primitives::traits::{Zero, Hash, As};
-use codec::Encode;
+use runtime_support::dispatch::{Result, Dispatchable, Parameter};
+use runtime_primitives::traits::{Zero, One, Hash, As, EnsureOrigin};
+use runtime_primitives::Permill;
+use codec::{Encode, Decode};
+use voting::VoteResult;
 
-pub use voting::voting::{Tally, VoteType, VoteOutcome, TallyType};
+pub use voting::voting::{Tally, VoteType, VoteOutcome, OutcomeLabel, TallyType, VoteThreshold};
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, PartialEq, Clone, Copy)]
@@ -48,12 +52,24 @@ pub enum ProposalStage {
 	PreVoting,
 	Voting,
 	Completed,
+	Cancelled,
+	// Passed and awaiting enactment, during which the proposal may be vetoed
+	Enacting,
+	Vetoed,
+	// Not seconded enough before the seconding deadline
+	Expired,
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, PartialEq, Clone, Copy)]
 pub enum ProposalCategory {
 	Signaling,
+	// Dispatches the encoded call in the proposal's payload if its vote passes
+	Action,
+	// Pays the encoded (beneficiary, amount) in the proposal's payload from the treasury if its vote passes
+	Funding,
+	// Sets the runtime code to the preimage of the code hash in the proposal's payload if its vote passes
+	RuntimeUpgrade,
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -66,15 +82,103 @@ pub struct ProposalRecord<AccountId, Moment> {
 	pub category: ProposalCategory,
 	pub title: Vec<u8>,
 	pub contents: Vec<u8>,
-	// TODO: for actions, we might need more data
 	pub vote_id: u64,
+	// Category-specific data: the encoded call of an `Action` proposal, the
+	// encoded beneficiary and amount of a `Funding` proposal or the encoded
+	// code hash of a `RuntimeUpgrade` proposal
+	pub payload: Vec<u8>,
 }
 
-pub trait Trait: voting::Trait + balances::Trait {
+/// A proposal record as stored before proposals carried a payload
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, PartialEq)]
+pub struct LegacyProposalRecord<AccountId, Moment> {
+	pub index: u32,
+	pub author: AccountId,
+	pub stage: ProposalStage,
+	pub transition_time: Moment,
+	pub category: ProposalCategory,
+	pub title: Vec<u8>,
+	pub contents: Vec<u8>,
+	pub vote_id: u64,
+}
+
+/// The current version of the module's storage layout
+const STORAGE_VERSION: u32 = 1;
+
+/// The origin of calls dispatched by passed proposals
+#[derive(PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub enum RawOrigin {
+	/// A passed `Action` proposal, by its index
+	Proposal(u32),
+	/// A passed `Funding` proposal approving its payout, by its index
+	Funding(u32),
+}
+
+/// Origin for the governance module.
+pub type Origin = RawOrigin;
+
+/// Ensures a call was dispatched by a passed proposal, yielding its index
+pub struct EnsureProposal;
+impl<O: Into<result::Result<RawOrigin, O>>> EnsureOrigin<O> for EnsureProposal {
+	type Success = u32;
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into() {
+			Ok(RawOrigin::Proposal(index)) => Ok(index),
+			_ => Err("bad origin: expected to be a passed governance proposal"),
+		}
+	}
+}
+
+/// Ensures a call approves the payout of a passed `Funding` proposal, yielding its index
+pub struct EnsureFunding;
+impl<O: Into<result::Result<RawOrigin, O>>> EnsureOrigin<O> for EnsureFunding {
+	type Success = u32;
+	fn ensure_origin(o: O) -> result::Result<Self::Success, &'static str> {
+		match o.into() {
+			Ok(RawOrigin::Funding(index)) => Ok(index),
+			_ => Err("bad origin: expected to be a passed funding proposal"),
+		}
+	}
+}
+
+/// Pays out passed `Funding` proposals. Implemented by the runtime on top of
+/// its treasury.
+pub trait FundingHandler<AccountId, Balance> {
+	/// The bond the proposer of a spend of `value` must hold when it is funded,
+	/// reserved by governance from proposal creation until enactment.
+	fn bond(value: Balance) -> Balance;
+	/// Creates and approves a spend of `value` to `beneficiary` for the passed
+	/// proposal with index `proposal_index`, made by `proposer`.
+	fn fund(proposal_index: u32, proposer: &AccountId, beneficiary: AccountId, value: Balance) -> Result;
+}
+
+impl<AccountId, Balance: Zero> FundingHandler<AccountId, Balance> for () {
+	fn bond(_value: Balance) -> Balance {
+		Zero::zero()
+	}
+
+	fn fund(_proposal_index: u32, _proposer: &AccountId, _beneficiary: AccountId, _value: Balance) -> Result {
+		Err("No funding source is configured")
+	}
+}
+
+pub trait Trait: voting::Trait + balances::Trait + consensus::Trait {
 	/// The overarching event type
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 	/// The account balance.
 	type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
+	/// The origin that may cancel any proposal before its vote completes.
+	type CancelOrigin: EnsureOrigin<<Self as system::Trait>::Origin>;
+	/// The origin that may veto a passed proposal before it is enacted.
+	type VetoOrigin: EnsureOrigin<<Self as system::Trait>::Origin>;
+	/// The overarching origin, which passed proposals dispatch their calls with.
+	type Origin: From<Origin>;
+	/// The runtime call an `Action` proposal dispatches.
+	type Proposal: Parameter + Dispatchable<Origin=<Self as Trait>::Origin>;
+	/// Pays out passed `Funding` proposals.
+	type Funding: FundingHandler<Self::AccountId, BalanceOf<Self>>;
 }
 
 pub type ProposalTitle = Vec<u8>;
@@ -84,22 +188,81 @@ pub static NO_VOTE: voting::voting::VoteOutcome = [0,0,0,0,0,0,0,0,0,0,0,0,0,0,0
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
 decl_module! {
-	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+	pub struct Module<T: Trait> for enum Call where origin: <T as system::Trait>::Origin {
 		fn deposit_event<T>() = default;
 
-		/// Creates a new governance proposal in the chosen category.
+		/// Migrate proposals stored in an older layout before any call touches them.
+		fn on_initialize(_n: T::BlockNumber) {
+			if Self::storage_version() < STORAGE_VERSION {
+				Self::migrate_proposal_records();
+			}
+		}
+
+		/// Creates a new governance proposal in the chosen category. The proposal's
+		/// vote is managed by this module, so its author cannot advance or cancel
+		/// it through the voting module.
+		///
+		/// `Action` proposals carry an encoded call as their payload, which is
+		/// dispatched with the governance origin if their binary vote passes.
+		/// `Funding` proposals carry an encoded beneficiary and amount, which
+		/// are paid from the treasury if their binary vote passes.
+		/// `RuntimeUpgrade` proposals carry the encoded hash of a noted code
+		/// preimage, which becomes the runtime code if their binary vote passes.
+		///
+		/// These categories must be put to a one coin vote between `YES_VOTE` and
+		/// `NO_VOTE` that meets the `ExecutionQuorum` of the total issuance. Actions
+		/// and funding pass under adaptive quorum biasing, runtime upgrades need
+		/// two thirds of the turnout. Passed proposals of these categories are
+		/// enacted after the category's enactment delay unless vetoed in the meantime.
 		pub fn create_proposal(
 			origin,
 			title: ProposalTitle,
 			contents: ProposalContents,
 			category: ProposalCategory,
+			payload: Vec<u8>,
 			outcomes: Vec<VoteOutcome>,
+			labels: Vec<OutcomeLabel>,
 			vote_type: voting::VoteType,
 			tally_type: voting::TallyType
 		) -> Result {
 			let _sender = ensure_signed(origin)?;
 			ensure!(!title.is_empty(), "Proposal must have title");
 			ensure!(!contents.is_empty(), "Proposal must not be empty");
+			// Proposals that enact something are decided by a yes/no vote of coins
+			if category != ProposalCategory::Signaling {
+				ensure!(vote_type == VoteType::Binary, "Executable proposals must be binary votes");
+				ensure!(tally_type == TallyType::OneCoin, "Executable proposals must be one coin votes");
+				ensure!(outcomes == vec![YES_VOTE, NO_VOTE], "Executable proposals must vote yes or no");
+			}
+			let mut funding_bond = BalanceOf::<T>::zero();
+			match category {
+				ProposalCategory::Signaling => ensure!(payload.is_empty(), "Signaling proposals carry no payload"),
+				ProposalCategory::Action => {
+					ensure!(
+						<T as Trait>::Proposal::decode(&mut &payload[..]).is_some(),
+						"Action proposal must carry a valid call"
+					);
+				},
+				ProposalCategory::Funding => {
+					let funding = <(T::AccountId, BalanceOf<T>)>::decode(&mut &payload[..]);
+					let value = funding.map_or(Zero::zero(), |(_, value)| value);
+					ensure!(!value.is_zero(), "Funding proposal must carry a beneficiary and amount");
+					funding_bond = T::Funding::bond(value);
+				},
+				ProposalCategory::RuntimeUpgrade => {
+					let code_hash = <T::Hash as Decode>::decode(&mut &payload[..]);
+					ensure!(
+						code_hash.map_or(false, |code_hash| <Preimages<T>>::exists(code_hash)),
+						"Runtime upgrade proposal must reference a noted preimage"
+					);
+				},
+			}
+			<voting::Module<T>>::validate_vote(vote_type, false, &outcomes, &labels, None)?;
+			let payload_hash = Self::payload_hash(category, &payload);
+			ensure!(
+				Self::cooldown_of(payload_hash).map_or(true, |end| <system::Module<T>>::block_number() >= end),
+				"Proposal is cooling off after a veto"
+			);
 
 			// construct hash(origin + proposal) and check existence
 			// TODO: include title/category/etc?
@@ -108,9 +271,17 @@ decl_module! {
 			buf.extend_from_slice(&contents.as_ref());
 			let hash = T::Hashing::hash(&buf[..]);
 			ensure!(<ProposalOf<T>>::get(hash) == None, "Proposal already exists");
+			ensure!(!<ArchivedProposals<T>>::exists(hash), "Proposal already exists");
 
-			// Reserve the proposal creation bond amount
-			T::Currency::reserve(&_sender, Self::proposal_creation_bond()).map_err(|_| "Not enough currency for reserve bond")?;
+			// Reserve the proposal creation bond amount, along with the bond a
+			// funding proposal's spend will need so it can be paid on enactment.
+			// The vote's deposit is reserved from the same balance next, so both
+			// are checked first to leave nothing reserved if either fails.
+			let bond = Self::proposal_creation_bond() + funding_bond;
+			let vote_deposit = BalanceOf::<T>::sa(<voting::Module<T>>::vote_deposit().as_());
+			ensure!(T::Currency::can_reserve(&_sender, bond + vote_deposit), "Not enough currency for reserve bond");
+			T::Currency::reserve(&_sender, bond)
+				.map_err(|_| "Not enough currency for reserve bond")?;
 			// create a vote to go along with the proposal
 			let vote_id = <voting::Module<T>>::create_vote(
 				_sender.clone(),
@@ -118,27 +289,59 @@ decl_module! {
 				false, // not commit-reveal
 				tally_type,
 				outcomes,
+				labels,
+				None, // advanced by governance
 			)?;
+			<voting::Module<T>>::set_managed(vote_id)?;
+			if let Some(threshold) = Self::passing_threshold(category) {
+				let quorum = Self::execution_quorum() * <balances::Module<T>>::total_issuance();
+				<voting::Module<T>>::set_passing_rules(vote_id, Some(quorum), Some(threshold))?;
+			}
 
 			let index = <ProposalCount<T>>::get();
 			<ProposalCount<T>>::mutate(|i| *i += 1);
+			let seconding_deadline = <system::Module<T>>::block_number() + Self::seconding_period();
 			<ProposalOf<T>>::insert(hash, ProposalRecord {
 				index: index,
 				author: _sender.clone(),
 				stage: ProposalStage::PreVoting,
 				category: category,
-				transition_time: T::BlockNumber::zero(),
+				transition_time: seconding_deadline,
 				title: title,
 				contents: contents,
 				vote_id: vote_id,
+				payload: payload,
 			});
 			<Proposals<T>>::mutate(|proposals| proposals.push(hash));
+			<SecondingDeadlines<T>>::mutate(seconding_deadline, |proposals| proposals.push(hash));
+			<Cooldowns<T>>::remove(payload_hash);
+			if !funding_bond.is_zero() {
+				<FundingBonds<T>>::insert(hash, funding_bond);
+			}
 			Self::deposit_event(RawEvent::NewProposal(_sender, hash));
 			Ok(())
 		}
 
+		/// Second a proposal in the "pre-voting" stage, reserving the seconding
+		/// deposit until the proposal advances, is cancelled or expires.
+		pub fn second_proposal(origin, proposal_hash: T::Hash) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
+			ensure!(record.stage == ProposalStage::PreVoting, "Proposal not in pre-voting stage");
+			ensure!(record.author != _sender, "Proposal cannot be seconded by its author");
+			let mut seconds = <Seconds<T>>::get(&proposal_hash);
+			ensure!(!seconds.contains(&_sender), "Proposal already seconded by account");
+
+			T::Currency::reserve(&_sender, Self::seconding_deposit()).map_err(|_| "Not enough currency for seconding deposit")?;
+			seconds.push(_sender.clone());
+			<Seconds<T>>::insert(proposal_hash, seconds);
+			Self::deposit_event(RawEvent::ProposalSeconded(proposal_hash, _sender));
+			Ok(())
+		}
+
 		/// Advance a governance proposal into the "voting" stage. Can only be
-		/// performed by the original author of the proposal.
+		/// performed by the original author of the proposal, once enough other
+		/// accounts have seconded it.
 		pub fn advance_proposal(origin, proposal_hash: T::Hash) -> Result {
 			let _sender = ensure_signed(origin)?;
 			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
@@ -146,9 +349,14 @@ decl_module! {
 			// only permit original author to advance
 			ensure!(record.author == _sender, "Proposal must be advanced by author");
 			ensure!(record.stage == ProposalStage::PreVoting, "Proposal not in pre-voting stage");
+			ensure!(
+				<Seconds<T>>::get(&proposal_hash).len() as u32 >= Self::seconding_threshold(),
+				"Proposal has not been seconded enough"
+			);
 			
 			// prevoting -> voting
 			<voting::Module<T>>::advance_stage(record.vote_id)?;
+			Self::return_seconds(proposal_hash, record.transition_time);
 			let transition_time = <system::Module<T>>::block_number() + Self::voting_length();
 			let vote_id = record.vote_id;
 			<ProposalOf<T>>::insert(proposal_hash, ProposalRecord {
@@ -161,9 +369,122 @@ decl_module! {
 			Ok(())
 		}
 
+		/// Cancel a governance proposal before its vote completes. Can only be
+		/// performed by the original author of the proposal.
+		pub fn cancel_proposal(origin, proposal_hash: T::Hash) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
+			ensure!(record.author == _sender, "Proposal must be cancelled by author");
+			return Self::cancel(proposal_hash, record);
+		}
+
+		/// Cancel any governance proposal before its vote completes, e.g. one
+		/// created with the wrong outcomes.
+		pub fn force_cancel_proposal(origin, proposal_hash: T::Hash) -> Result {
+			T::CancelOrigin::ensure_origin(origin)?;
+			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
+			return Self::cancel(proposal_hash, record);
+		}
+
+		/// Veto a passed proposal before it is enacted, stating a reason. The
+		/// vetoed payload cannot be proposed again until the veto cooldown ends.
+		pub fn veto_proposal(origin, proposal_hash: T::Hash, reason: Vec<u8>) -> Result {
+			T::VetoOrigin::ensure_origin(origin)?;
+			ensure!(!reason.is_empty(), "Veto must state a reason");
+			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
+			ensure!(record.stage == ProposalStage::Enacting, "Proposal not in enacting stage");
+
+			<EnactmentQueue<T>>::mutate(record.transition_time, |proposals| proposals.retain(|hash| hash != &proposal_hash));
+			let cooldown_end = <system::Module<T>>::block_number() + Self::veto_cooldown();
+			<Cooldowns<T>>::insert(Self::payload_hash(record.category, &record.payload), cooldown_end);
+			Self::release_funding_bond(proposal_hash, &record.author);
+			<ProposalOf<T>>::insert(proposal_hash, ProposalRecord {
+				stage: ProposalStage::Vetoed,
+				transition_time: T::BlockNumber::zero(),
+				..record
+			});
+			Self::deposit_event(RawEvent::ProposalVetoed(proposal_hash, reason));
+			Ok(())
+		}
+
+		/// Prune a completed, cancelled, vetoed or expired proposal along with its vote once the
+		/// vote's retention period has passed. Votes with many voters take several
+		/// calls, and the proposal is archived by the call that removes its vote.
+		/// That call's sender receives the vote's deposit, and only the vote's
+		/// tally and result are kept.
+		pub fn prune_proposal(origin, proposal_hash: T::Hash) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <ProposalOf<T>>::get(&proposal_hash).ok_or("Proposal does not exist")?;
+			ensure!(
+				record.stage == ProposalStage::Completed
+					|| record.stage == ProposalStage::Cancelled
+					|| record.stage == ProposalStage::Vetoed
+					|| record.stage == ProposalStage::Expired,
+				"Proposal has not concluded"
+			);
+			// The vote may already have been pruned on its own
+			if <voting::Module<T>>::vote_of(record.vote_id).is_some()
+				&& !<voting::Module<T>>::prune(record.vote_id, &_sender)?
+			{
+				return Ok(());
+			}
+			<ProposalOf<T>>::remove(&proposal_hash);
+			<Proposals<T>>::mutate(|proposals| proposals.retain(|hash| hash != &proposal_hash));
+			<ArchivedProposals<T>>::insert(proposal_hash, record.vote_id);
+			Self::deposit_event(RawEvent::ProposalPruned(proposal_hash, _sender));
+			Ok(())
+		}
+
+		/// Note the preimage of a runtime upgrade, reserving a deposit for each
+		/// byte stored. The deposit is returned when the upgrade is enacted or
+		/// the sender removes the preimage.
+		pub fn note_preimage(origin, code: Vec<u8>) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let code_hash = T::Hashing::hash(&code[..]);
+			ensure!(!<Preimages<T>>::exists(code_hash), "Preimage already noted");
+
+			let deposit = Self::preimage_byte_deposit() * BalanceOf::<T>::sa(code.len() as u64);
+			T::Currency::reserve(&_sender, deposit).map_err(|_| "Not enough currency for preimage deposit")?;
+			<Preimages<T>>::insert(code_hash, (code, _sender.clone(), deposit));
+			Self::deposit_event(RawEvent::PreimageNoted(code_hash, _sender));
+			Ok(())
+		}
+
+		/// Remove a preimage noted by the sender and return its deposit. The
+		/// preimage cannot be removed while an upgrade proposal referencing it
+		/// has not concluded.
+		pub fn remove_preimage(origin, code_hash: T::Hash) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let (_, who, deposit) = <Preimages<T>>::get(code_hash).ok_or("Preimage does not exist")?;
+			ensure!(who == _sender, "Preimage must be removed by its uploader");
+			ensure!(
+				!Self::proposals().into_iter()
+					.filter_map(|hash| <ProposalOf<T>>::get(hash))
+					.any(|record| record.category == ProposalCategory::RuntimeUpgrade
+						&& (record.stage == ProposalStage::PreVoting
+							|| record.stage == ProposalStage::Voting
+							|| record.stage == ProposalStage::Enacting)
+						&& <T::Hash as Decode>::decode(&mut &record.payload[..]) == Some(code_hash)),
+				"Preimage is referenced by a live proposal"
+			);
+			<Preimages<T>>::remove(code_hash);
+			T::Currency::unreserve(&who, deposit);
+			Ok(())
+		}
+
 		/// Check all active proposals to see if they're completed. If so, update
-		/// them in storage and emit an event.
+		/// them in storage and emit an event. Passed proposals with something to
+		/// enact are scheduled, and those scheduled for this block are enacted.
+		/// Proposals still in "pre-voting" at their seconding deadline expire.
 		fn on_finalize(_n: T::BlockNumber) {
+			for proposal_hash in <SecondingDeadlines<T>>::take(_n) {
+				match <ProposalOf<T>>::get(proposal_hash) {
+					Some(ref record) if record.stage != ProposalStage::PreVoting => { },
+					Some(record) => Self::expire(proposal_hash, record),
+					None => { },
+				}
+			}
+
 			let (finished, active): (Vec<_>, _) = <ActiveProposals<T>>::get()
 				.into_iter()
 				.partition(|(_, exp)| _n > *exp);
@@ -171,6 +492,8 @@ decl_module! {
 			<ActiveProposals<T>>::put(active);
 			finished.into_iter().for_each(move |(completed_hash, _)| {
 				match <ProposalOf<T>>::get(completed_hash) {
+					// Skip proposals cancelled by an earlier proposal in this block
+					Some(ref record) if record.stage != ProposalStage::Voting => { },
 					Some(record) => {
 						// voting -> completed
 						let vote_id = record.vote_id;
@@ -178,18 +501,197 @@ decl_module! {
 						let _ = <voting::Module<T>>::advance_stage(vote_id);
 						// Unreserve the proposal creation bond amount
 						T::Currency::unreserve(&record.author, Self::proposal_creation_bond());
-						// Edit the proposal record to completed
-						<ProposalOf<T>>::insert(completed_hash, ProposalRecord {
-							stage: ProposalStage::Completed,
-							transition_time: T::BlockNumber::zero(),
-							..record
-						});
 						Self::deposit_event(RawEvent::VotingCompleted(completed_hash, vote_id));
+						let passed = <voting::Module<T>>::result_of(vote_id) == Some(VoteResult::Passed);
+						match Self::enactment_delay(record.category) {
+							Some(delay) if passed => {
+								// Enact no sooner than the next block, leaving time to veto
+								let enact_at = _n + cmp::max(delay, T::BlockNumber::one());
+								<ProposalOf<T>>::insert(completed_hash, ProposalRecord {
+									stage: ProposalStage::Enacting,
+									transition_time: enact_at,
+									..record
+								});
+								<EnactmentQueue<T>>::mutate(enact_at, |proposals| proposals.push(completed_hash));
+								Self::deposit_event(RawEvent::EnactmentScheduled(completed_hash, enact_at));
+							},
+							// Edit the proposal record to completed
+							_ => {
+								Self::release_funding_bond(completed_hash, &record.author);
+								<ProposalOf<T>>::insert(completed_hash, ProposalRecord {
+									stage: ProposalStage::Completed,
+									transition_time: T::BlockNumber::zero(),
+									..record
+								});
+							},
+						}
 					},
 					None => { } // TODO: emit an error here?
 				}
 			});
+
+			for proposal_hash in <EnactmentQueue<T>>::take(_n) {
+				if let Some(record) = <ProposalOf<T>>::get(proposal_hash) {
+					// enacting -> completed
+					let record = ProposalRecord {
+						stage: ProposalStage::Completed,
+						transition_time: T::BlockNumber::zero(),
+						..record
+					};
+					<ProposalOf<T>>::insert(proposal_hash, &record);
+					Self::execute(proposal_hash, &record);
+				}
+			}
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// The number of blocks a passed proposal waits before being enacted, or
+	/// `None` for categories with nothing to enact
+	pub fn enactment_delay(category: ProposalCategory) -> Option<T::BlockNumber> {
+		match category {
+			ProposalCategory::Signaling => None,
+			ProposalCategory::Action => Some(Self::action_delay()),
+			ProposalCategory::Funding => Some(Self::funding_delay()),
+			ProposalCategory::RuntimeUpgrade => Some(Self::upgrade_delay()),
+		}
+	}
+
+	/// The threshold a proposal's vote must pass, or `None` for categories
+	/// with nothing to enact
+	pub fn passing_threshold(category: ProposalCategory) -> Option<VoteThreshold> {
+		match category {
+			ProposalCategory::Signaling => None,
+			ProposalCategory::Action | ProposalCategory::Funding => Some(VoteThreshold::SuperMajorityApprove),
+			ProposalCategory::RuntimeUpgrade => Some(VoteThreshold::SuperMajority(2, 3)),
+		}
+	}
+
+	/// Rewrites proposal records stored before proposals carried a payload with
+	/// an empty one. Extant proposals are already listed under `Proposals`, so
+	/// they are migrated together, before `on_finalize` can read them.
+	fn migrate_proposal_records() {
+		for hash in Self::proposals() {
+			let key = <ProposalOf<T>>::key_for(&hash);
+			let old = match runtime_support::storage::get::<LegacyProposalRecord<T::AccountId, T::BlockNumber>>(&key) {
+				Some(old) => old,
+				None => continue,
+			};
+			<ProposalOf<T>>::insert(hash, ProposalRecord {
+				index: old.index,
+				author: old.author,
+				stage: old.stage,
+				transition_time: old.transition_time,
+				category: old.category,
+				title: old.title,
+				contents: old.contents,
+				vote_id: old.vote_id,
+				payload: vec![],
+			});
+		}
+		<StorageVersion<T>>::put(STORAGE_VERSION);
+	}
+
+	/// Identifies what a proposal would enact, so a vetoed payload cannot be
+	/// resubmitted under a new title or by another author during its cooldown
+	fn payload_hash(category: ProposalCategory, payload: &[u8]) -> T::Hash {
+		let mut buf = category.encode();
+		buf.extend_from_slice(payload);
+		T::Hashing::hash(&buf[..])
+	}
+
+	/// Enacts a passed proposal: dispatches the call of an `Action` proposal
+	/// with the governance origin, pays out a `Funding` proposal or sets the
+	/// runtime code to the preimage of a `RuntimeUpgrade` proposal, returning
+	/// the preimage deposit
+	fn execute(proposal_hash: T::Hash, record: &ProposalRecord<T::AccountId, T::BlockNumber>) {
+		let ok = match record.category {
+			ProposalCategory::Signaling => return,
+			ProposalCategory::RuntimeUpgrade => {
+				let preimage = <T::Hash as Decode>::decode(&mut &record.payload[..])
+					.and_then(|code_hash| <Preimages<T>>::take(code_hash));
+				match preimage {
+					Some((code, who, deposit)) => {
+						T::Currency::unreserve(&who, deposit);
+						<consensus::Module<T>>::set_code(code).is_ok()
+					},
+					None => false,
+				}
+			},
+			ProposalCategory::Action => match <T as Trait>::Proposal::decode(&mut &record.payload[..]) {
+				Some(call) => call.dispatch(RawOrigin::Proposal(record.index).into()).is_ok(),
+				None => return,
+			},
+			ProposalCategory::Funding => {
+				// The spend reserves its own bond from the freed funds
+				Self::release_funding_bond(proposal_hash, &record.author);
+				match <(T::AccountId, BalanceOf<T>)>::decode(&mut &record.payload[..]) {
+					Some((beneficiary, value)) => T::Funding::fund(record.index, &record.author, beneficiary, value).is_ok(),
+					None => return,
+				}
+			},
+		};
+		Self::deposit_event(RawEvent::ProposalExecuted(proposal_hash, ok));
+	}
+
+	/// Returns the bond reserved for a funding proposal's spend to its author
+	fn release_funding_bond(proposal_hash: T::Hash, author: &T::AccountId) {
+		if let Some(bond) = <FundingBonds<T>>::take(proposal_hash) {
+			T::Currency::unreserve(author, bond);
+		}
+	}
+
+	/// Returns the deposits of a proposal's seconders and removes it from the
+	/// seconding deadline index
+	fn return_seconds(proposal_hash: T::Hash, seconding_deadline: T::BlockNumber) {
+		for seconder in <Seconds<T>>::take(&proposal_hash) {
+			T::Currency::unreserve(&seconder, Self::seconding_deposit());
+		}
+		<SecondingDeadlines<T>>::mutate(seconding_deadline, |proposals| proposals.retain(|hash| hash != &proposal_hash));
+	}
+
+	/// Expires a proposal that was not seconded enough before its deadline,
+	/// cancelling its vote, returning the seconders' deposits and slashing
+	/// part of the creation bond
+	fn expire(proposal_hash: T::Hash, record: ProposalRecord<T::AccountId, T::BlockNumber>) {
+		// TODO: handle possible errors from cancel_vote?
+		let _ = <voting::Module<T>>::cancel_vote(record.vote_id);
+		Self::return_seconds(proposal_hash, record.transition_time);
+		let bond = Self::proposal_creation_bond();
+		let slash = Self::expiry_slash() * bond;
+		let _ = T::Currency::slash_reserved(&record.author, slash);
+		T::Currency::unreserve(&record.author, bond - slash);
+		Self::release_funding_bond(proposal_hash, &record.author);
+		<ProposalOf<T>>::insert(proposal_hash, ProposalRecord {
+			stage: ProposalStage::Expired,
+			transition_time: T::BlockNumber::zero(),
+			..record
+		});
+		Self::deposit_event(RawEvent::ProposalExpired(proposal_hash));
+	}
+
+	/// Cancels a proposal along with its vote and returns the creation bond
+	/// and any seconding deposits
+	fn cancel(proposal_hash: T::Hash, record: ProposalRecord<T::AccountId, T::BlockNumber>) -> Result {
+		ensure!(
+			record.stage == ProposalStage::PreVoting || record.stage == ProposalStage::Voting,
+			"Proposal not in pre-voting or voting stage"
+		);
+		<voting::Module<T>>::cancel_vote(record.vote_id)?;
+		if record.stage == ProposalStage::PreVoting {
+			Self::return_seconds(proposal_hash, record.transition_time);
 		}
+		T::Currency::unreserve(&record.author, Self::proposal_creation_bond());
+		Self::release_funding_bond(proposal_hash, &record.author);
+		<ActiveProposals<T>>::mutate(|proposals| proposals.retain(|(hash, _)| hash != &proposal_hash));
+		<ProposalOf<T>>::insert(proposal_hash, ProposalRecord {
+			stage: ProposalStage::Cancelled,
+			transition_time: T::BlockNumber::zero(),
+			..record
+		});
+		Self::deposit_event(RawEvent::ProposalCancelled(proposal_hash));
+		Ok(())
 	}
 }
 
@@ -203,6 +705,22 @@ decl_event!(
 		VotingStarted(Hash, u64, BlockNumber),
 		/// Emitted when voting is completed: (ProposalHash, VoteId, VoteResults)
 		VotingCompleted(Hash, u64),
+		/// Emitted when a proposal is cancelled before its vote completes: (ProposalHash)
+		ProposalCancelled(Hash),
+		/// Emitted when an account seconds a proposal: (ProposalHash, Seconder)
+		ProposalSeconded(Hash, AccountId),
+		/// Emitted when a proposal is not seconded enough before its deadline: (ProposalHash)
+		ProposalExpired(Hash),
+		/// Emitted when a runtime code preimage is noted: (CodeHash, Uploader)
+		PreimageNoted(Hash, AccountId),
+		/// Emitted when a passed proposal is scheduled for enactment: (ProposalHash, EnactmentBlock)
+		EnactmentScheduled(Hash, BlockNumber),
+		/// Emitted when a passed proposal is vetoed before enactment: (ProposalHash, Reason)
+		ProposalVetoed(Hash, Vec<u8>),
+		/// Emitted when a passed action, funding or runtime upgrade proposal is enacted: (ProposalHash, Succeeded)
+		ProposalExecuted(Hash, bool),
+		/// Emitted when a concluded proposal is pruned from storage: (ProposalHash, Pruner)
+		ProposalPruned(Hash, AccountId),
 	}
 );
 
@@ -218,6 +736,42 @@ decl_storage! {
 		pub VotingLength get(voting_length) config(): T::BlockNumber;
 		/// Map for retrieving the information about any proposal from its hash. 
 		pub ProposalOf get(proposal_of): map T::Hash => Option<ProposalRecord<T::AccountId, T::BlockNumber>>;
+		/// The vote id of each pruned proposal, to look up its tally and result
+		pub ArchivedProposals get(archived_vote_of): map T::Hash => Option<u64>;
+		/// The bond reserved from the author of each funding proposal for its spend, until it is enacted or fails
+		pub FundingBonds get(funding_bond_of): map T::Hash => Option<BalanceOf<T>>;
+		/// The code, uploader and deposit of each noted runtime code preimage, by code hash
+		pub Preimages get(preimage_of): map T::Hash => Option<(Vec<u8>, T::AccountId, BalanceOf<T>)>;
+		/// The passed proposals to enact at each block
+		pub EnactmentQueue get(enactments_at): map T::BlockNumber => Vec<T::Hash>;
+		/// Number of blocks between an action proposal passing and being enacted
+		pub ActionDelay get(action_delay) config(): T::BlockNumber;
+		/// Number of blocks between a funding proposal passing and being enacted
+		pub FundingDelay get(funding_delay) config(): T::BlockNumber;
+		/// Number of blocks between a runtime upgrade passing and being enacted
+		pub UpgradeDelay get(upgrade_delay) config(): T::BlockNumber;
+		/// Number of blocks after a veto before its payload may be proposed again
+		pub VetoCooldown get(veto_cooldown) config(): T::BlockNumber;
+		/// The block until which each vetoed payload, by category and payload hash, may not be proposed
+		pub Cooldowns get(cooldown_of): map T::Hash => Option<T::BlockNumber>;
+		/// Deposit reserved per byte of a noted preimage
+		pub PreimageByteDeposit get(preimage_byte_deposit) config(): BalanceOf<T>;
+		/// The accounts that have seconded each proposal in the "pre-voting" stage
+		pub Seconds get(seconds_of): map T::Hash => Vec<T::AccountId>;
+		/// The proposals whose seconding period ends at each block
+		pub SecondingDeadlines get(seconding_deadlines_at): map T::BlockNumber => Vec<T::Hash>;
+		/// Number of blocks a proposal has to be seconded enough before it expires
+		pub SecondingPeriod get(seconding_period) config(): T::BlockNumber;
+		/// Number of seconds a proposal needs before it may enter voting
+		pub SecondingThreshold get(seconding_threshold) config(): u32;
+		/// Deposit reserved by each seconder until the proposal advances, is cancelled or expires
+		pub SecondingDeposit get(seconding_deposit) config(): BalanceOf<T>;
+		/// Portion of the creation bond slashed when a proposal expires
+		pub ExpirySlash get(expiry_slash) config(): Permill;
+		/// The minimum turnout of an executable proposal's vote, as a portion of the total issuance
+		pub ExecutionQuorum get(execution_quorum) config(): Permill;
+		/// The version of the storage layout, raised once old data is migrated
+		pub StorageVersion get(storage_version): u32;
 		/// Registration bond
 		pub ProposalCreationBond get(proposal_creation_bond) config(): BalanceOf<T> = BalanceOf::<T>::sa(10);
 	}
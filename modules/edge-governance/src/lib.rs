@@ -47,7 +47,7 @@ extern crate edge_voting as voting;
 
 pub mod governance;
 pub use governance::{
-	Module, Trait, RawEvent, Event,
+	Module, Trait, RawEvent, Event, RawOrigin, Origin, EnsureProposal, EnsureFunding,
 	ProposalStage, ProposalCategory, ProposalRecord
 };
 
@@ -56,22 +56,29 @@ mod tests {
 	use super::*;
 	use rstd::prelude::*;
 	use codec::Encode;
+	use rstd::result;
 	use runtime_support::dispatch::Result;
+	use runtime_support::{StorageValue, StorageMap};
+	use runtime_support::traits::Currency;
 	use system::{EventRecord, Phase};
 	use runtime_io::with_externalities;
 	use primitives::{H256, Blake2Hasher, Hasher};
+	use primitives::storage::well_known_keys;
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
 	use runtime_primitives::{
 		BuildStorage,
-		traits::{BlakeTwo256, OnFinalize, IdentityLookup},
-		testing::{Digest, DigestItem, Header, UintAuthorityId}
+		traits::{BlakeTwo256, OnFinalize, OnInitialize, IdentityLookup, EnsureOrigin},
+		testing::{Digest, DigestItem, Header, UintAuthorityId},
+		Permill,
 	};
-	use voting::{VoteStage, VoteType};
+	use voting::{VoteStage, VoteType, VoteResult};
 	use voting::voting::{VoteOutcome, TallyType};
 
 	impl_outer_origin! {
-		pub enum Origin for Test {}
+		pub enum Origin for Test {
+			governance
+		}
 	}
 
 	impl_outer_event! {
@@ -81,7 +88,9 @@ mod tests {
 	}
 
 	impl_outer_dispatch! {
-		pub enum Call for Test where origin: Origin {}
+		pub enum Call for Test where origin: Origin {
+			governance::Governance,
+		}
 	}
 
 	// For testing the module, we construct most of a mock runtime. This means
@@ -119,15 +128,47 @@ mod tests {
 	}
 	impl delegation::Trait for Test {
 		type Event = Event;
+		type OnDelegationChange = voting::Module<Test>;
 	}
 
 	impl voting::Trait for Test {
 		type Event = Event;
+		type IdentityVerifier = ();
+	}
+
+	// Lets root or a passed action proposal force cancel proposals
+	pub struct EnsureRootOrProposal;
+	impl EnsureOrigin<Origin> for EnsureRootOrProposal {
+		type Success = ();
+		fn ensure_origin(o: Origin) -> result::Result<(), &'static str> {
+			match o.into() {
+				Ok(governance::RawOrigin::Proposal(_)) => Ok(()),
+				Ok(_) => Err("bad origin: expected root or a passed action proposal"),
+				Err(o) => system::EnsureRoot::<u64>::ensure_origin(o).map(|_| ()),
+			}
+		}
+	}
+
+	// Pays funding proposals from account 4, standing in for a treasury with a 10% bond
+	pub struct TestFunding;
+	impl governance::FundingHandler<u64, u64> for TestFunding {
+		fn bond(value: u64) -> u64 {
+			value / 10
+		}
+
+		fn fund(_proposal_index: u32, _proposer: &u64, beneficiary: u64, value: u64) -> Result {
+			<Balances as Currency<u64>>::transfer(&4, &beneficiary, value)
+		}
 	}
 
 	impl Trait for Test {
 		type Event = Event;
 		type Currency = balances::Module<Self>;
+		type CancelOrigin = EnsureRootOrProposal;
+		type VetoOrigin = system::EnsureRoot<u64>;
+		type Origin = Origin;
+		type Proposal = Call;
+		type Funding = TestFunding;
 	}
 
 	pub type Balances = balances::Module<Test>;
@@ -143,6 +184,16 @@ mod tests {
 			governance::GenesisConfig::<Test> {
 				voting_length: 10000,
 				proposal_creation_bond: BOND,
+				action_delay: 2,
+				funding_delay: 2,
+				upgrade_delay: 5,
+				veto_cooldown: 100,
+				seconding_period: 100,
+				seconding_threshold: 0,
+				seconding_deposit: 5,
+				expiry_slash: Permill::from_percent(50),
+				preimage_byte_deposit: 1,
+				execution_quorum: Permill::from_percent(10),
 			}.build_storage().unwrap().0,
 		);
 		t.extend(
@@ -178,15 +229,60 @@ mod tests {
 			title.to_vec(),
 			proposal.to_vec(),
 			category,
+			vec![],
 			outcomes,
+			vec![],
 			vote_type,
 			tally_type)
 	}
 
+	fn propose_action(who: u64, title: &[u8], proposal: &[u8], call: Call, vote_type: VoteType) -> Result {
+		Governance::create_proposal(
+			Origin::signed(who),
+			title.to_vec(),
+			proposal.to_vec(),
+			governance::ProposalCategory::Action,
+			call.encode(),
+			vec![governance::YES_VOTE, governance::NO_VOTE],
+			vec![],
+			vote_type,
+			TallyType::OneCoin)
+	}
+
+	fn propose_funding(who: u64, title: &[u8], proposal: &[u8], payload: Vec<u8>) -> Result {
+		Governance::create_proposal(
+			Origin::signed(who),
+			title.to_vec(),
+			proposal.to_vec(),
+			governance::ProposalCategory::Funding,
+			payload,
+			vec![governance::YES_VOTE, governance::NO_VOTE],
+			vec![],
+			VoteType::Binary,
+			TallyType::OneCoin)
+	}
+
+	fn propose_upgrade(who: u64, title: &[u8], proposal: &[u8], payload: Vec<u8>) -> Result {
+		Governance::create_proposal(
+			Origin::signed(who),
+			title.to_vec(),
+			proposal.to_vec(),
+			governance::ProposalCategory::RuntimeUpgrade,
+			payload,
+			vec![governance::YES_VOTE, governance::NO_VOTE],
+			vec![],
+			VoteType::Binary,
+			TallyType::OneCoin)
+	}
+
 	fn advance_proposal(who: u64, proposal_hash: H256) -> Result {
 		Governance::advance_proposal(Origin::signed(who), proposal_hash)
 	}
 
+	fn cancel_proposal(who: u64, proposal_hash: H256) -> Result {
+		Governance::cancel_proposal(Origin::signed(who), proposal_hash)
+	}
+
 	fn build_proposal_hash(who: u64, proposal: &[u8]) -> H256 {
 			let mut buf = Vec::new();
 			buf.extend_from_slice(&who.encode());
@@ -216,10 +312,11 @@ mod tests {
 				author: author,
 				stage: ProposalStage::PreVoting,
 				category: category,
-				transition_time: 0,
+				transition_time: 101,
 				title: title.to_vec(),
 				contents: contents.to_vec(),
 				vote_id: 1,
+				payload: vec![],
 			}
 	}
 
@@ -490,6 +587,14 @@ mod tests {
 					phase: Phase::ApplyExtrinsic(0),
 					event: Event::voting(voting::RawEvent::VoteAdvanced(vote_id, VoteStage::Voting, VoteStage::Completed))
 				},
+				EventRecord {
+					phase: Phase::ApplyExtrinsic(0),
+					event: Event::voting(voting::RawEvent::VoteCompleted(
+						vote_id,
+						vec![(governance::YES_VOTE, 0), (governance::NO_VOTE, 0)],
+						VoteResult::Failed
+					))
+				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
 					event: Event::governance(RawEvent::VotingCompleted(
@@ -599,4 +704,741 @@ mod tests {
 			assert_eq!(balance, after_completion_balance);
 		});
 	}
-} 
+
+	#[test]
+	fn cancel_proposal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert_ok!(advance_proposal(public, hash));
+			assert_eq!(Balances::reserved_balance(public), BOND);
+
+			assert_err!(cancel_proposal(2, hash), "Proposal must be cancelled by author");
+			assert_ok!(cancel_proposal(public, hash));
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Governance::active_proposals(), vec![]);
+			assert_eq!(
+				Governance::proposal_of(hash),
+				Some(ProposalRecord {
+					stage: ProposalStage::Cancelled,
+					transition_time: 0,
+					..make_record(public, title, proposal, category)
+				})
+			);
+			assert_eq!(voting::Module::<Test>::vote_of(1).unwrap().data.stage, VoteStage::Cancelled);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalCancelled(hash)));
+			assert_err!(cancel_proposal(public, hash), "Proposal not in pre-voting or voting stage");
+		});
+	}
+
+	#[test]
+	fn force_cancel_proposal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert!(Governance::force_cancel_proposal(Origin::signed(public), hash).is_err());
+			assert_ok!(Governance::force_cancel_proposal(Origin::ROOT, hash));
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Cancelled);
+			assert_eq!(Balances::reserved_balance(public), 0);
+		});
+	}
+
+	#[test]
+	fn propose_with_labels_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(Governance::create_proposal(
+				Origin::signed(public),
+				title.to_vec(),
+				proposal.to_vec(),
+				governance::ProposalCategory::Signaling,
+				vec![],
+				outcomes,
+				vec![b"Yes".to_vec(), b"No".to_vec()],
+				VoteType::Binary,
+				TallyType::OneCoin
+			));
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_eq!(voting::Module::<Test>::label_of(vote_id, &governance::NO_VOTE), Some(b"No".to_vec()));
+		});
+	}
+
+	#[test]
+	fn propose_with_invalid_vote_should_not_reserve() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_err!(
+				Governance::create_proposal(
+					Origin::signed(public),
+					title.to_vec(),
+					proposal.to_vec(),
+					governance::ProposalCategory::Signaling,
+					vec![],
+					outcomes.clone(),
+					vec![b"Yes".to_vec()],
+					VoteType::Binary,
+					TallyType::OneCoin
+				),
+				"Outcome labels do not match outcomes"
+			);
+			assert_err!(
+				propose(public, title, proposal, governance::ProposalCategory::Signaling, outcomes, VoteType::MultiOption, TallyType::OneCoin),
+				"Invalid multi option outcomes"
+			);
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Governance::proposal_count(), 0);
+		});
+	}
+
+	#[test]
+	fn propose_without_vote_deposit_should_not_reserve() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			// The bond fits, but not together with the vote's deposit
+			<voting::voting::VoteDeposit<Test>>::put(100 - BOND + 1);
+			assert_err!(
+				propose(public, title, proposal, category, outcomes.clone(), VoteType::Binary, TallyType::OneCoin),
+				"Not enough currency for reserve bond"
+			);
+			assert_eq!(Balances::reserved_balance(public), 0);
+			<voting::voting::VoteDeposit<Test>>::put(100 - BOND);
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert_eq!(Balances::reserved_balance(public), 100);
+		});
+	}
+
+	#[test]
+	fn prune_proposal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::voting::VoteDeposit<Test>>::put(5);
+			<voting::voting::RetentionPeriod<Test>>::put(10);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes.clone(), VoteType::Binary, TallyType::OneCoin));
+			assert_eq!(Balances::reserved_balance(public), BOND + 5);
+			assert_ok!(advance_proposal(public, hash));
+			assert_err!(Governance::prune_proposal(Origin::signed(2), hash), "Proposal has not concluded");
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Completed);
+			assert_err!(
+				Governance::prune_proposal(Origin::signed(2), hash),
+				"Vote is still within its retention period"
+			);
+
+			System::set_block_number(10012);
+			assert_ok!(Governance::prune_proposal(Origin::signed(2), hash));
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Balances::free_balance(2), 105);
+			assert_eq!(Governance::proposal_of(hash), None);
+			assert_eq!(Governance::proposals(), vec![]);
+			assert_eq!(Governance::archived_vote_of(hash), Some(1));
+			assert_eq!(voting::Module::<Test>::vote_of(1), None);
+			assert_eq!(
+				voting::Module::<Test>::tally_of(1),
+				Some(vec![(governance::YES_VOTE, 0), (governance::NO_VOTE, 0)])
+			);
+			assert_eq!(
+				System::events().last().unwrap().event,
+				Event::governance(RawEvent::ProposalPruned(hash, 2))
+			);
+			assert_err!(
+				propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin),
+				"Proposal already exists"
+			);
+		});
+	}
+
+	#[test]
+	fn propose_action_should_validate_payload() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let call = Call::Governance(governance::Call::force_cancel_proposal(H256::zero()));
+			assert_err!(
+				propose_action(public, title, proposal, call.clone(), VoteType::MultiOption),
+				"Executable proposals must be binary votes"
+			);
+			assert_err!(
+				Governance::create_proposal(
+					Origin::signed(public),
+					title.to_vec(),
+					proposal.to_vec(),
+					governance::ProposalCategory::Action,
+					vec![0xff],
+					vec![governance::YES_VOTE, governance::NO_VOTE],
+					vec![],
+					VoteType::Binary,
+					TallyType::OneCoin
+				),
+				"Action proposal must carry a valid call"
+			);
+			assert_err!(
+				Governance::create_proposal(
+					Origin::signed(public),
+					title.to_vec(),
+					proposal.to_vec(),
+					governance::ProposalCategory::Signaling,
+					call.encode(),
+					vec![governance::YES_VOTE, governance::NO_VOTE],
+					vec![],
+					VoteType::Binary,
+					TallyType::OneCoin
+				),
+				"Signaling proposals carry no payload"
+			);
+			assert_ok!(propose_action(public, title, proposal, call.clone(), VoteType::Binary));
+			let hash = build_proposal_hash(public, &proposal);
+			assert_eq!(Governance::proposal_of(hash).unwrap().payload, call.encode());
+		});
+	}
+
+	#[test]
+	fn executable_proposals_should_be_managed_one_coin_yes_or_no_votes() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let payload = (5_u64, 30_u64).encode();
+			let create = |outcomes: Vec<VoteOutcome>, tally_type: TallyType| Governance::create_proposal(
+				Origin::signed(public),
+				title.to_vec(),
+				proposal.to_vec(),
+				governance::ProposalCategory::Funding,
+				payload.clone(),
+				outcomes,
+				vec![],
+				VoteType::Binary,
+				tally_type
+			);
+			assert_err!(
+				create(vec![governance::YES_VOTE, governance::NO_VOTE], TallyType::OnePerson),
+				"Executable proposals must be one coin votes"
+			);
+			assert_err!(
+				create(vec![governance::NO_VOTE, governance::YES_VOTE], TallyType::OneCoin),
+				"Executable proposals must vote yes or no"
+			);
+			assert_ok!(create(vec![governance::YES_VOTE, governance::NO_VOTE], TallyType::OneCoin));
+			let hash = build_proposal_hash(public, &proposal);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			// A tenth of the issuance of 400 must turn out
+			let data = voting::Module::<Test>::vote_of(vote_id).unwrap().data;
+			assert_eq!(data.quorum, Some(40));
+			assert_eq!(data.threshold, Some(governance::VoteThreshold::SuperMajorityApprove));
+			assert_eq!(
+				Governance::passing_threshold(governance::ProposalCategory::RuntimeUpgrade),
+				Some(governance::VoteThreshold::SuperMajority(2, 3))
+			);
+
+			// The author cannot bypass governance through the voting module
+			assert_err!(
+				voting::Module::<Test>::advance_stage_as_initiator(Origin::signed(public), vote_id),
+				"Vote is managed by another module"
+			);
+			assert_err!(
+				voting::Module::<Test>::cancel_vote_as_initiator(Origin::signed(public), vote_id),
+				"Vote is managed by another module"
+			);
+		});
+	}
+
+	#[test]
+	fn executable_proposal_without_quorum_should_not_enact() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<governance::ExecutionQuorum<Test>>::put(Permill::from_percent(50));
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			assert_ok!(propose_funding(public, title, proposal, (5_u64, 30_u64).encode()));
+			let hash = build_proposal_hash(public, &proposal);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(public, hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			assert_eq!(voting::Module::<Test>::result_of(vote_id), Some(VoteResult::NoQuorum));
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Completed);
+			assert_eq!(Governance::enactments_at(10004), vec![]);
+		});
+	}
+
+	#[test]
+	fn legacy_proposal_records_should_migrate() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			runtime_support::storage::put(
+				&<governance::ProposalOf<Test>>::key_for(&hash),
+				&governance::LegacyProposalRecord {
+					index: 0,
+					author: public,
+					stage: ProposalStage::PreVoting,
+					transition_time: 101_u64,
+					category: ProposalCategory::Signaling,
+					title: title.to_vec(),
+					contents: proposal.to_vec(),
+					vote_id: 1,
+				}
+			);
+			<governance::Proposals<Test>>::put(vec![hash]);
+			assert_eq!(Governance::storage_version(), 0);
+			<Governance as OnInitialize<u64>>::on_initialize(1);
+			assert_eq!(Governance::storage_version(), 1);
+			assert_eq!(
+				Governance::proposal_of(hash),
+				Some(make_record(public, title, proposal, ProposalCategory::Signaling))
+			);
+		});
+	}
+
+	#[test]
+	fn passed_action_proposal_should_dispatch_call() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(1, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			let signaling_hash = build_proposal_hash(1, &proposal);
+
+			// A proposal to cancel the signaling proposal, which passes
+			let call = Call::Governance(governance::Call::force_cancel_proposal(signaling_hash));
+			let action: &[u8] = b"Cancel the signaling proposal";
+			assert_ok!(propose_action(2, title, action, call, VoteType::Binary));
+			let action_hash = build_proposal_hash(2, action);
+			let vote_id = Governance::proposal_of(action_hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(2, action_hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(5);
+			assert_ok!(advance_proposal(1, signaling_hash));
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+
+			assert_eq!(voting::Module::<Test>::result_of(vote_id), Some(VoteResult::Passed));
+			assert_eq!(Governance::proposal_of(action_hash).unwrap().stage, ProposalStage::Enacting);
+			assert_eq!(Governance::proposal_of(signaling_hash).unwrap().stage, ProposalStage::Voting);
+
+			System::set_block_number(10004);
+			<Governance as OnFinalize<u64>>::on_finalize(10004);
+			assert_eq!(Governance::proposal_of(action_hash).unwrap().stage, ProposalStage::Completed);
+			assert_eq!(Governance::proposal_of(signaling_hash).unwrap().stage, ProposalStage::Cancelled);
+			assert!(System::events().iter().any(|e| {
+				e.event == Event::governance(RawEvent::ProposalExecuted(action_hash, true))
+			}));
+		});
+	}
+
+	#[test]
+	fn failed_action_proposal_should_not_dispatch_call() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(1, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			let signaling_hash = build_proposal_hash(1, &proposal);
+
+			let call = Call::Governance(governance::Call::force_cancel_proposal(signaling_hash));
+			let action: &[u8] = b"Cancel the signaling proposal";
+			assert_ok!(propose_action(2, title, action, call, VoteType::Binary));
+			let action_hash = build_proposal_hash(2, action);
+			let vote_id = Governance::proposal_of(action_hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(2, action_hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::NO_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+
+			assert_eq!(voting::Module::<Test>::result_of(vote_id), Some(VoteResult::Failed));
+			assert_eq!(Governance::proposal_of(action_hash).unwrap().stage, ProposalStage::Completed);
+			assert_eq!(Governance::enactments_at(10004), vec![]);
+			assert_eq!(Governance::proposal_of(signaling_hash).unwrap().stage, ProposalStage::PreVoting);
+			assert!(!System::events().iter().any(|e| {
+				e.event == Event::governance(RawEvent::ProposalExecuted(action_hash, true))
+			}));
+		});
+	}
+
+	#[test]
+	fn proposal_origin_should_only_accept_passed_proposals() {
+		assert_eq!(EnsureProposal::ensure_origin(Origin::from(RawOrigin::Proposal(3))), Ok(3));
+		assert!(EnsureProposal::ensure_origin(Origin::from(RawOrigin::Funding(3))).is_err());
+		assert!(EnsureProposal::ensure_origin(Origin::ROOT).is_err());
+		assert!(EnsureProposal::ensure_origin(Origin::signed(1)).is_err());
+		assert_eq!(EnsureFunding::ensure_origin(Origin::from(RawOrigin::Funding(3))), Ok(3));
+		assert!(EnsureFunding::ensure_origin(Origin::from(RawOrigin::Proposal(3))).is_err());
+		assert!(EnsureFunding::ensure_origin(Origin::ROOT).is_err());
+	}
+
+	#[test]
+	fn propose_funding_should_validate_payload() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			assert_err!(
+				propose_funding(public, title, proposal, vec![1, 2, 3]),
+				"Funding proposal must carry a beneficiary and amount"
+			);
+			assert_err!(
+				propose_funding(public, title, proposal, (5_u64, 0_u64).encode()),
+				"Funding proposal must carry a beneficiary and amount"
+			);
+			assert_err!(
+				Governance::create_proposal(
+					Origin::signed(public),
+					title.to_vec(),
+					proposal.to_vec(),
+					governance::ProposalCategory::Funding,
+					(5_u64, 30_u64).encode(),
+					vec![governance::YES_VOTE, governance::NO_VOTE, [2; 32]],
+					vec![],
+					VoteType::MultiOption,
+					TallyType::OneCoin
+				),
+				"Executable proposals must be binary votes"
+			);
+			assert_ok!(propose_funding(public, title, proposal, (5_u64, 30_u64).encode()));
+		});
+	}
+
+	#[test]
+	fn passed_funding_proposal_should_pay_beneficiary() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			assert_ok!(propose_funding(public, title, proposal, (5_u64, 30_u64).encode()));
+			let hash = build_proposal_hash(public, &proposal);
+			// The spend's bond is reserved along with the creation bond
+			assert_eq!(Governance::funding_bond_of(hash), Some(3));
+			assert_eq!(Balances::reserved_balance(public), BOND + 3);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(public, hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			assert_eq!(Balances::free_balance(5), 0);
+			assert_eq!(Balances::reserved_balance(public), 3);
+			System::set_block_number(10004);
+			<Governance as OnFinalize<u64>>::on_finalize(10004);
+			assert_eq!(Governance::funding_bond_of(hash), None);
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Balances::free_balance(5), 30);
+			assert_eq!(Balances::free_balance(4), 70);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalExecuted(hash, true)));
+		});
+	}
+
+	#[test]
+	fn funding_bond_should_be_reserved_at_creation() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			assert_err!(
+				propose_funding(public, title, proposal, (5_u64, 1000_u64).encode()),
+				"Not enough currency for reserve bond"
+			);
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_ok!(propose_funding(public, title, proposal, (5_u64, 300_u64).encode()));
+			assert_eq!(Balances::reserved_balance(public), BOND + 30);
+
+			let hash = build_proposal_hash(public, &proposal);
+			assert_ok!(cancel_proposal(public, hash));
+			assert_eq!(Governance::funding_bond_of(hash), None);
+			assert_eq!(Balances::reserved_balance(public), 0);
+		});
+	}
+
+	#[test]
+	fn unfunded_funding_proposal_should_report_failure() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			assert_ok!(propose_funding(public, title, proposal, (5_u64, 500_u64).encode()));
+			let hash = build_proposal_hash(public, &proposal);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(public, hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			System::set_block_number(10004);
+			<Governance as OnFinalize<u64>>::on_finalize(10004);
+			assert_eq!(Balances::free_balance(5), 0);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalExecuted(hash, false)));
+		});
+	}
+
+	#[test]
+	fn note_preimage_should_reserve_deposit() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let code = b"new runtime".to_vec();
+			let code_hash = Blake2Hasher::hash(&code[..]);
+			assert_ok!(Governance::note_preimage(Origin::signed(1), code.clone()));
+			assert_eq!(Balances::reserved_balance(1), 11);
+			assert_eq!(Governance::preimage_of(code_hash), Some((code.clone(), 1, 11)));
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::PreimageNoted(code_hash, 1)));
+			assert_err!(Governance::note_preimage(Origin::signed(2), code.clone()), "Preimage already noted");
+			assert_err!(Governance::note_preimage(Origin::signed(2), vec![0; 200]), "Not enough currency for preimage deposit");
+
+			assert_err!(Governance::remove_preimage(Origin::signed(2), code_hash), "Preimage must be removed by its uploader");
+			assert_ok!(Governance::remove_preimage(Origin::signed(1), code_hash));
+			assert_eq!(Balances::reserved_balance(1), 0);
+			assert_eq!(Governance::preimage_of(code_hash), None);
+			assert_err!(Governance::remove_preimage(Origin::signed(1), code_hash), "Preimage does not exist");
+		});
+	}
+
+	#[test]
+	fn propose_upgrade_should_require_noted_preimage() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let code = b"new runtime".to_vec();
+			let code_hash = Blake2Hasher::hash(&code[..]);
+			assert_err!(
+				propose_upgrade(public, title, proposal, code_hash.encode()),
+				"Runtime upgrade proposal must reference a noted preimage"
+			);
+			assert_err!(
+				propose_upgrade(public, title, proposal, vec![1, 2, 3]),
+				"Runtime upgrade proposal must reference a noted preimage"
+			);
+			assert_ok!(Governance::note_preimage(Origin::signed(2), code));
+			assert_ok!(propose_upgrade(public, title, proposal, code_hash.encode()));
+		});
+	}
+
+	#[test]
+	fn passed_upgrade_proposal_should_set_code_after_delay() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let code = b"new runtime".to_vec();
+			let code_hash = Blake2Hasher::hash(&code[..]);
+			assert_ok!(Governance::note_preimage(Origin::signed(2), code.clone()));
+			assert_ok!(propose_upgrade(public, title, proposal, code_hash.encode()));
+			let hash = build_proposal_hash(public, &proposal);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_ok!(advance_proposal(public, hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::EnactmentScheduled(hash, 10007)));
+			assert_eq!(Governance::enactments_at(10007), vec![hash]);
+			assert_eq!(runtime_io::storage(well_known_keys::CODE), None);
+
+			System::set_block_number(10007);
+			<Governance as OnFinalize<u64>>::on_finalize(10007);
+			assert_eq!(runtime_io::storage(well_known_keys::CODE), Some(code));
+			assert_eq!(Governance::preimage_of(code_hash), None);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalExecuted(hash, true)));
+		});
+	}
+
+	#[test]
+	fn referenced_preimage_should_not_be_removed() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let code = b"new runtime".to_vec();
+			let code_hash = Blake2Hasher::hash(&code[..]);
+			assert_ok!(Governance::note_preimage(Origin::signed(2), code));
+			assert_ok!(propose_upgrade(public, title, proposal, code_hash.encode()));
+			let hash = build_proposal_hash(public, &proposal);
+			assert_err!(Governance::remove_preimage(Origin::signed(2), code_hash), "Preimage is referenced by a live proposal");
+
+			assert_ok!(advance_proposal(public, hash));
+			assert_err!(Governance::remove_preimage(Origin::signed(2), code_hash), "Preimage is referenced by a live proposal");
+
+			assert_ok!(Governance::force_cancel_proposal(Origin::ROOT, hash));
+			assert_ok!(Governance::remove_preimage(Origin::signed(2), code_hash));
+			assert_eq!(Governance::preimage_of(code_hash), None);
+			assert_eq!(Balances::reserved_balance(2), 0);
+		});
+	}
+
+	#[test]
+	fn veto_should_stop_enactment_and_block_resubmission() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (title, proposal) = generate_proposal();
+			let payload = (5_u64, 30_u64).encode();
+			assert_ok!(propose_funding(public, title, proposal, payload.clone()));
+			let hash = build_proposal_hash(public, &proposal);
+			let vote_id = Governance::proposal_of(hash).unwrap().vote_id;
+			assert_err!(Governance::veto_proposal(Origin::ROOT, hash, b"Too much".to_vec()), "Proposal not in enacting stage");
+			assert_ok!(advance_proposal(public, hash));
+			assert_ok!(voting::Module::<Test>::reveal(Origin::signed(3), vote_id, vec![governance::YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<u64>>::on_finalize(10002);
+			assert_eq!(
+				Governance::proposal_of(hash),
+				Some(ProposalRecord {
+					stage: ProposalStage::Enacting,
+					transition_time: 10004,
+					payload: payload.clone(),
+					..make_record(public, title, proposal, governance::ProposalCategory::Funding)
+				})
+			);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::EnactmentScheduled(hash, 10004)));
+
+			System::set_block_number(10003);
+			assert!(Governance::veto_proposal(Origin::signed(2), hash, b"Too much".to_vec()).is_err());
+			assert_err!(Governance::veto_proposal(Origin::ROOT, hash, vec![]), "Veto must state a reason");
+			assert_ok!(Governance::veto_proposal(Origin::ROOT, hash, b"Too much".to_vec()));
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Vetoed);
+			assert_eq!(Governance::funding_bond_of(hash), None);
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Governance::enactments_at(10004), vec![]);
+			assert_eq!(
+				System::events().last().unwrap().event,
+				Event::governance(RawEvent::ProposalVetoed(hash, b"Too much".to_vec()))
+			);
+
+			System::set_block_number(10004);
+			<Governance as OnFinalize<u64>>::on_finalize(10004);
+			assert_eq!(Balances::free_balance(5), 0);
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Vetoed);
+
+			// The same payload may not be proposed again, even by someone else, until the cooldown ends
+			let resubmission: &[u8] = b"Fund account 5 again";
+			assert_err!(propose_funding(2, title, resubmission, payload.clone()), "Proposal is cooling off after a veto");
+			assert_ok!(propose_funding(2, title, resubmission, (5_u64, 20_u64).encode()));
+			System::set_block_number(10103);
+			assert_ok!(propose_funding(4, title, resubmission, payload.clone()));
+			assert_eq!(Governance::cooldown_of(Blake2Hasher::hash(&[&[2u8][..], &payload[..]].concat())), None);
+		});
+	}
+
+	#[test]
+	fn seconding_should_gate_advance() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<governance::SecondingThreshold<Test>>::put(2);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert_eq!(Governance::seconding_deadlines_at(101), vec![hash]);
+
+			assert_err!(Governance::second_proposal(Origin::signed(public), hash), "Proposal cannot be seconded by its author");
+			assert_ok!(Governance::second_proposal(Origin::signed(2), hash));
+			assert_eq!(Balances::reserved_balance(2), 5);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalSeconded(hash, 2)));
+			assert_err!(Governance::second_proposal(Origin::signed(2), hash), "Proposal already seconded by account");
+			assert_err!(advance_proposal(public, hash), "Proposal has not been seconded enough");
+
+			assert_ok!(Governance::second_proposal(Origin::signed(3), hash));
+			assert_eq!(Governance::seconds_of(hash), vec![2, 3]);
+			assert_ok!(advance_proposal(public, hash));
+			assert_eq!(Governance::seconds_of(hash), vec![]);
+			assert_eq!(Governance::seconding_deadlines_at(101), vec![]);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_err!(Governance::second_proposal(Origin::signed(4), hash), "Proposal not in pre-voting stage");
+		});
+	}
+
+	#[test]
+	fn unseconded_proposal_should_expire() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<governance::SecondingThreshold<Test>>::put(2);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert_ok!(Governance::second_proposal(Origin::signed(2), hash));
+
+			System::set_block_number(101);
+			<Governance as OnFinalize<u64>>::on_finalize(101);
+			assert_eq!(
+				Governance::proposal_of(hash),
+				Some(ProposalRecord {
+					stage: ProposalStage::Expired,
+					transition_time: 0,
+					..make_record(public, title, proposal, category)
+				})
+			);
+			assert_eq!(System::events().last().unwrap().event, Event::governance(RawEvent::ProposalExpired(hash)));
+			// Half of the creation bond is slashed and the seconding deposit returned
+			assert_eq!(Balances::reserved_balance(public), 0);
+			assert_eq!(Balances::free_balance(public), 100 - BOND / 2);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 100);
+			assert_eq!(Governance::seconds_of(hash), vec![]);
+			assert_err!(Governance::second_proposal(Origin::signed(3), hash), "Proposal not in pre-voting stage");
+			assert_err!(advance_proposal(public, hash), "Proposal not in pre-voting stage");
+		});
+	}
+
+	#[test]
+	fn cancelled_proposal_should_return_seconding_deposits() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let category = governance::ProposalCategory::Signaling;
+			let (title, proposal) = generate_proposal();
+			let hash = build_proposal_hash(public, &proposal);
+			let outcomes = vec![governance::YES_VOTE, governance::NO_VOTE];
+			assert_ok!(propose(public, title, proposal, category, outcomes, VoteType::Binary, TallyType::OneCoin));
+			assert_ok!(Governance::second_proposal(Origin::signed(2), hash));
+			assert_ok!(cancel_proposal(public, hash));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Governance::seconding_deadlines_at(101), vec![]);
+
+			System::set_block_number(101);
+			<Governance as OnFinalize<u64>>::on_finalize(101);
+			assert_eq!(Governance::proposal_of(hash).unwrap().stage, ProposalStage::Cancelled);
+		});
+	}
+}
@@ -35,6 +35,7 @@ extern crate srml_balances as balances;
 use srml_support::traits::{Currency, ReservableCurrency};
 
 use rstd::prelude::*;
+use rstd::cmp;
 use runtime_primitives::traits::{Zero, Hash, As};
 use runtime_support::dispatch::Result;
 use runtime_support::{StorageMap, StorageValue};
@@ -54,6 +55,11 @@ pub type Identity = Vec<u8>;
 type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
 
+/// The current version of the module's storage layout
+const STORAGE_VERSION: u32 = 1;
+/// The number of identities indexed by account per block while migrating
+const MIGRATION_BATCH: u32 = 100;
+
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, PartialEq)]
 pub struct MetadataRecord {
@@ -231,22 +237,71 @@ decl_module! {
 
 			expired.into_iter().for_each(move |(exp_hash, _)| {
 				<Identities<T>>::mutate(|idents| idents.retain(|hash| hash != &exp_hash));
-				<IdentityOf<T>>::remove(exp_hash);
+				if let Some(record) = <IdentityOf<T>>::take(exp_hash) {
+					<IdentitiesOf<T>>::mutate(record.account, |hashes| hashes.retain(|hash| hash != &exp_hash));
+				}
 				Self::deposit_event(RawEvent::Expired(exp_hash))
 			});
 			<IdentitiesPending<T>>::put(valid);
 		}
+
+		/// Index identities registered before `IdentitiesOf` existed, a batch per block.
+		fn on_initialize(_n: T::BlockNumber) {
+			if Self::storage_version() < STORAGE_VERSION {
+				Self::migrate_identities_of();
+			}
+		}
 	}
 }
 
 impl<T: Trait> Module<T> {
+	/// Adds a batch of identities to the `IdentitiesOf` index of their accounts.
+	///
+	/// Identities are indexed from the end of `Identities` towards its start,
+	/// so removals while the migration is under way never skip an identity.
+	/// Identities registered since the upgrade are already indexed.
+	fn migrate_identities_of() {
+		let identities = Self::identities();
+		let end = cmp::min(
+			<MigrationCursor<T>>::get().unwrap_or(identities.len() as u32) as usize,
+			identities.len()
+		);
+		let start = end.saturating_sub(MIGRATION_BATCH as usize);
+		for hash in identities[start..end].iter() {
+			if let Some(record) = Self::identity_of(hash) {
+				<IdentitiesOf<T>>::mutate(record.account, |hashes| if !hashes.contains(hash) {
+					hashes.push(*hash);
+				});
+			}
+		}
+		if start == 0 {
+			<MigrationCursor<T>>::kill();
+			<StorageVersion<T>>::put(STORAGE_VERSION);
+		} else {
+			<MigrationCursor<T>>::put(start as u32);
+		}
+	}
+
 	/// Removes all data about a pending identity given the hash of the record
 	pub fn remove_pending_identity(identity_hash: &T::Hash) {
+		if let Some(record) = <IdentityOf<T>>::get(identity_hash) {
+			<IdentitiesOf<T>>::mutate(record.account, |hashes| hashes.retain(|hash| hash != identity_hash));
+		}
 		<Identities<T>>::mutate(|idents| idents.retain(|hash| hash != identity_hash));
 		<IdentityOf<T>>::remove(identity_hash);
 		<IdentitiesPending<T>>::mutate(|idents| idents.retain(|(hash, _)| hash != identity_hash));
 	}
 
+	/// Whether an account holds a verified identity, of one of the given
+	/// identity types if any are given
+	pub fn is_verified(who: &T::AccountId, identity_types: &[IdentityType]) -> bool {
+		Self::identities_of(who)
+			.iter()
+			.filter_map(|hash| Self::identity_of(hash))
+			.any(|record| record.stage == IdentityStage::Verified &&
+				(identity_types.is_empty() || identity_types.contains(&record.identity_type)))
+	}
+
 	/// Helper function for executing the verification of identities
 	fn verify_or_deny_identity(sender: T::AccountId, identity_hash: &T::Hash, approve: bool) -> Result {
 		let record = <IdentityOf<T>>::get(identity_hash).ok_or("Identity does not exist")?;
@@ -291,6 +346,7 @@ impl<T: Trait> Module<T> {
 		let expiration = now + Self::expiration_length();
 		// Add identity record
 		<Identities<T>>::mutate(|idents| idents.push(identity_hash.clone()));
+		<IdentitiesOf<T>>::mutate(sender.clone(), |hashes| hashes.push(identity_hash.clone()));
 		<IdentityOf<T>>::insert(identity_hash, IdentityRecord {
 			account: sender.clone(),
 			identity_type: identity_type,
@@ -358,6 +414,8 @@ decl_storage! {
 		pub Identities get(identities): Vec<(T::Hash)>;
 		/// Actual identity for a given hash, if it's current.
 		pub IdentityOf get(identity_of): map T::Hash => Option<IdentityRecord<T::AccountId, T::BlockNumber>>;
+		/// The hashes of the current identities of each account
+		pub IdentitiesOf get(identities_of): map T::AccountId => Vec<T::Hash>;
 		/// List of identities awaiting attestation or verification and associated expirations
 		pub IdentitiesPending get(identities_pending): Vec<(T::Hash, T::BlockNumber)>;
 		/// Number of blocks allowed between register/attest or attest/verify.
@@ -368,5 +426,9 @@ decl_storage! {
 		pub Verifiers get(verifiers) config(): Vec<T::AccountId>;
 		/// Registration bond
 		pub RegistrationBond get(registration_bond) config(): BalanceOf<T> = BalanceOf::<T>::sa(10);
+		/// The version of the storage layout, raised once old data is migrated
+		pub StorageVersion get(storage_version): u32;
+		/// The position in `Identities` the migration indexes next batch up to
+		pub MigrationCursor: Option<u32>;
 	}
 }
@@ -59,12 +59,13 @@ mod tests {
 	use rstd::prelude::*;
 	use runtime_io::with_externalities;
 	use runtime_support::dispatch::Result;
+	use runtime_support::{StorageMap, StorageValue};
 	use system::{EventRecord, Phase};
 	// The testing primitives are very useful for avoiding having to work with
 	// public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
 	use runtime_primitives::{
 		testing::{Digest, DigestItem, Header, UintAuthorityId},
-		traits::{BlakeTwo256, OnFinalize, IdentityLookup},
+		traits::{BlakeTwo256, OnFinalize, OnInitialize, IdentityLookup},
 		BuildStorage,
 	};
 
@@ -520,7 +521,11 @@ mod tests {
 				]
 			);
 			assert_eq!(Identity::identities(), vec![identity_hash]);
+			assert_eq!(Identity::identities_of(public), vec![identity_hash]);
 			assert_eq!(Identity::identities_pending(), vec![]);
+			assert!(Identity::is_verified(&public, &[]));
+			assert!(Identity::is_verified(&public, &[identity_type.to_vec()]));
+			assert!(!Identity::is_verified(&public, &[b"twitter".to_vec()]));
 			assert_eq!(
 				Identity::identity_of(identity_hash),
 				Some(IdentityRecord {
@@ -702,11 +707,37 @@ mod tests {
 				]
 			);
 			assert_eq!(Identity::identities(), vec![]);
+			assert_eq!(Identity::identities_of(public), vec![]);
 			assert_eq!(Identity::identities_pending(), vec![]);
 			assert_eq!(Identity::identity_of(identity_hash), None);
 		});
 	}
 
+	#[test]
+	fn identities_of_should_be_migrated() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let hash_1 = build_identity_hash(b"github", b"drewstone");
+			let hash_2 = build_identity_hash(b"twitter", b"drewstone");
+			let hash_3 = build_identity_hash(b"github", b"jnaviask");
+			assert_ok!(register_identity(1, b"github", b"drewstone"));
+			assert_ok!(register_identity(1, b"twitter", b"drewstone"));
+			assert_ok!(register_identity(2, b"github", b"jnaviask"));
+			// Identities registered before accounts indexed them
+			<identity::IdentitiesOf<Test>>::remove(1);
+			<identity::IdentitiesOf<Test>>::remove(2);
+
+			<Identity as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Identity::storage_version(), 1);
+			assert_eq!(Identity::identities_of(1), vec![hash_1, hash_2]);
+			assert_eq!(Identity::identities_of(2), vec![hash_3]);
+			// The migration runs once
+			<identity::IdentitiesOf<Test>>::remove(2);
+			<Identity as OnInitialize<u64>>::on_initialize(3);
+			assert_eq!(Identity::identities_of(2), vec![]);
+		});
+	}
+
 	#[test]
 	fn add_metadata_should_work() {
 		with_externalities(&mut new_test_ext(), || {
@@ -0,0 +1,74 @@
+// Copyright 2018 Commonwealth Labs, Inc.
+// This file is part of Edgeware.
+
+// Edgeware is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Edgeware is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Edgeware.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client-side helpers for commit-reveal votes.
+//!
+//! A commitment is `H(COMMITMENT_PREFIX ++ vote_id ++ account ++ secret ++ ballot)`
+//! where `H` is the runtime's hashing function, the vote id, account and
+//! secret are SCALE encoded and the ballot is its outcomes concatenated in
+//! order. Approval ballots must be sorted ascending, as the runtime sorts them
+//! before checking. Split votes cover each part's outcome followed by its
+//! encoded amount instead.
+
+use runtime_primitives::traits::Hash;
+use codec::Encode;
+use rand;
+
+use voting::{VoteOutcome, commitment_preimage, ballot_bytes, split_ballot_bytes};
+
+/// Generates a random secret to commit with
+pub fn generate_secret() -> VoteOutcome {
+	rand::random()
+}
+
+/// Builds the commitment of an account to a ballot in a vote
+pub fn build<H: Hash, AccountId: Encode>(
+	vote_id: u64,
+	who: &AccountId,
+	secret: &VoteOutcome,
+	ballot: &[VoteOutcome]
+) -> VoteOutcome {
+	hash_preimage::<H>(&commitment_preimage(vote_id, who, secret, &ballot_bytes(ballot)))
+}
+
+/// Builds the commitment of an account to a split vote in a vote
+pub fn build_split<H: Hash, AccountId: Encode, Balance: Encode>(
+	vote_id: u64,
+	who: &AccountId,
+	secret: &VoteOutcome,
+	parts: &[(VoteOutcome, Balance)]
+) -> VoteOutcome {
+	hash_preimage::<H>(&commitment_preimage(vote_id, who, secret, &split_ballot_bytes(parts)))
+}
+
+/// Checks locally whether revealing a ballot with a secret matches a commitment
+pub fn verify<H: Hash, AccountId: Encode>(
+	commitment: &VoteOutcome,
+	vote_id: u64,
+	who: &AccountId,
+	secret: &VoteOutcome,
+	ballot: &[VoteOutcome]
+) -> bool {
+	&build::<H, AccountId>(vote_id, who, secret, ballot) == commitment
+}
+
+fn hash_preimage<H: Hash>(preimage: &[u8]) -> VoteOutcome {
+	let mut commitment = VoteOutcome::default();
+	let hash = H::hash(preimage);
+	let len = hash.as_ref().len().min(commitment.len());
+	commitment[..len].copy_from_slice(&hash.as_ref()[..len]);
+	commitment
+}
@@ -42,10 +42,14 @@ extern crate sr_io as runtime_io;
 extern crate srml_balances as balances;
 extern crate srml_system as system;
 extern crate edge_delegation as delegation;
+#[cfg(feature = "std")]
+extern crate rand;
 
 pub mod voting;
+#[cfg(feature = "std")]
+pub mod commitment;
 pub use voting::{Module, Trait, RawEvent, Event};
-pub use voting::{VoteStage, VoteType, TallyType, VoteRecord, VoteData};
+pub use voting::{VoteStage, VoteType, TallyType, Vote, VoteRecord, VoteData, VoteThreshold, VoteResult};
 
 // Tests for Delegation Module
 #[cfg(test)]
@@ -53,6 +57,8 @@ mod tests {
 	use super::*;
 	use rstd::prelude::*;
 	use runtime_support::dispatch::Result;
+	use runtime_support::{StorageMap, StorageValue};
+	use runtime_support::traits::MakePayment;
 	use codec::Encode;
 	use system::{EventRecord, Phase};
 	use runtime_io::with_externalities;
@@ -61,7 +67,7 @@ mod tests {
 	// The testing primitives are very useful for avoiding having to work with signatures
 	// or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
 	use runtime_primitives::{
-		BuildStorage, traits::{BlakeTwo256, Hash, IdentityLookup},
+		BuildStorage, traits::{BlakeTwo256, Hash, IdentityLookup, OnFinalize, OnInitialize},
 		testing::{Digest, DigestItem, Header}
 	};
 
@@ -112,19 +118,35 @@ mod tests {
 
 	impl delegation::Trait for Test {
 		type Event = Event;
+		type OnDelegationChange = Voting;
+	}
+
+	// Accounts 1 to 3 hold verified identities of type "github"
+	pub struct TestIdentityVerifier;
+	impl voting::IdentityVerifier<u64> for TestIdentityVerifier {
+		fn is_verified(who: &u64, identity_types: &[Vec<u8>]) -> bool {
+			*who <= 3 && (identity_types.is_empty() || identity_types.contains(&b"github".to_vec()))
+		}
 	}
 
 	impl Trait for Test {
 		type Event = Event;
+		type IdentityVerifier = TestIdentityVerifier;
 	}
 
 	pub type System = system::Module<Test>;
+	pub type Balances = balances::Module<Test>;
 	pub type Delegation = delegation::Module<Test>;
 	pub type Voting = Module<Test>;
 
 	// This function basically just builds a genesis storage key/value store according to
 	// our desired mockup.
 	fn new_test_ext() -> sr_io::TestExternalities<Blake2Hasher> {
+		new_test_ext_with_fee(0)
+	}
+
+	// Builds the same genesis with a base fee charged on every transaction
+	fn new_test_ext_with_fee(transaction_base_fee: u64) -> sr_io::TestExternalities<Blake2Hasher> {
 		let mut t = system::GenesisConfig::<Test>::default().build_storage().unwrap().0;
 		t.extend(
 			delegation::delegation::GenesisConfig::<Test> {
@@ -132,6 +154,25 @@ mod tests {
 				_genesis_phantom_data: Default::default(),
 			}.build_storage().unwrap().0,
 		);
+		t.extend(
+			balances::GenesisConfig::<Test> {
+				balances: vec![
+					(1, 10),
+					(2, 20),
+					(3, 30),
+					(4, 40),
+					(5, 50),
+					(6, 60),
+					(7, 70),
+				],
+				transaction_base_fee: transaction_base_fee,
+				transaction_byte_fee: 0,
+				existential_deposit: 0,
+				transfer_fee: 0,
+				creation_fee: 0,
+				vesting: vec![],
+			}.build_storage().unwrap().0,
+		);
 		// We use default for brevity, but you can configure as desired if needed.
 		t.into()
 	}
@@ -147,7 +188,31 @@ mod tests {
 							vote_type,
 							is_commit_reveal,
 							tally_type,
-							outcomes.to_vec())
+							outcomes.to_vec(),
+							vec![],
+							None)
+	}
+
+	fn create_scheduled_vote(
+		who: u64,
+		vote_type: voting::VoteType,
+		is_commit_reveal: bool,
+		tally_type: voting::TallyType,
+		outcomes: &[[u8; 32]],
+		stage_lengths: (u64, u64)
+	) -> result::Result<u64, &'static str> {
+		Voting::create_vote(who,
+							vote_type,
+							is_commit_reveal,
+							tally_type,
+							outcomes.to_vec(),
+							vec![],
+							Some(stage_lengths))
+	}
+
+	fn finalize_block(n: u64) {
+		System::set_block_number(n);
+		<Voting as OnFinalize<u64>>::on_finalize(n);
 	}
 
 	fn commit(who: u64, vote_id: u64, commit: [u8; 32]) -> Result {
@@ -155,7 +220,15 @@ mod tests {
 	}
 
 	fn reveal(who: u64, vote_id: u64, vote: [u8; 32], secret: Option<[u8; 32]>) -> Result {
-		Voting::reveal(Origin::signed(who), vote_id, vote, secret)
+		Voting::reveal(Origin::signed(who), vote_id, vec![vote], secret)
+	}
+
+	fn reveal_ballot(who: u64, vote_id: u64, ballot: &[[u8; 32]], secret: Option<[u8; 32]>) -> Result {
+		Voting::reveal(Origin::signed(who), vote_id, ballot.to_vec(), secret)
+	}
+
+	fn reveal_split(who: u64, vote_id: u64, parts: &[([u8; 32], u64)], secret: Option<[u8; 32]>) -> Result {
+		Voting::reveal_split(Origin::signed(who), vote_id, parts.to_vec(), secret)
 	}
 
 	fn advance_stage_as_initiator(who: u64, vote_id: u64) -> Result {
@@ -166,6 +239,10 @@ mod tests {
 		Delegation::delegate_to(Origin::signed(who), to)
 	}
 
+	fn transfer(who: u64, to: u64, value: u64) -> Result {
+		Balances::transfer(Origin::signed(who), to, value)
+	}
+
 	fn get_test_key() -> u64 {
 		let public = 1_u64;
 		return public;
@@ -213,6 +290,11 @@ mod tests {
 		]);
 	}
 
+	fn generate_1p1v_ranked_choice_vote() -> (voting::VoteType, bool, voting::TallyType, [[u8; 32]; 4]) {
+		let (_, is_commit_reveal, tally_type, outcomes) = generate_1p1v_public_multi_vote();
+		return (VoteType::RankedChoice, is_commit_reveal, tally_type, outcomes);
+	}
+
 	fn make_record(
 		id: u64,
 		author: u64,
@@ -221,18 +303,21 @@ mod tests {
 		tally_type: voting::TallyType,
 		outcomes: &[[u8; 32]],
 		stage: VoteStage
-	) -> VoteRecord<u64> {
+	) -> VoteRecord<u64, u64> {
 		VoteRecord {
 			id: id,
 			commitments: vec![],
 			reveals: vec![],
 			outcomes: outcomes.to_vec(),
+			labels: vec![],
 			data: VoteData {
 				initiator: author,
 				stage: stage,
 				vote_type: vote_type,
 				tally_type: tally_type,
 				is_commit_reveal: is_commit_reveal,
+				quorum: None,
+				threshold: None,
 			},
 		}
 	}
@@ -405,7 +490,7 @@ mod tests {
 			assert_ok!(reveal(public2, 1, vote.3[0], Some(vote.3[0])));
 			assert_eq!(
 				Voting::vote_records(1).unwrap().reveals,
-				vec![(public2, vote.3[0])]
+				vec![(public2, vec![vote.3[0]])]
 			);
 			assert_eq!(System::events(), vec![
 				EventRecord {
@@ -418,7 +503,7 @@ mod tests {
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
-					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vote.3[0]))
+					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vec![vote.3[0]]))
 				}
 			]);
 		});
@@ -480,16 +565,61 @@ mod tests {
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
-					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vote.3[0]))
+					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vec![vote.3[0]]))
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
 					event: Event::voting(voting::RawEvent::VoteAdvanced(1, VoteStage::Voting, VoteStage::Completed))
+				},
+				EventRecord {
+					phase: Phase::ApplyExtrinsic(0),
+					event: Event::voting(voting::RawEvent::VoteCompleted(1, vec![(vote.3[0], 1), (vote.3[1], 0)], VoteResult::Passed))
 				}
 			]);
 		});
 	}
 
+	#[test]
+	fn one_person_tally_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_multi_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(reveal(3, 1, vote.3[2], None));
+			assert_eq!(Voting::tally_of(1), None);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![
+				(vote.3[0], 2),
+				(vote.3[1], 0),
+				(vote.3[2], 1),
+				(vote.3[3], 0),
+			]));
+		});
+	}
+
+	#[test]
+	fn one_coin_tally_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[1], None));
+			assert_ok!(reveal(3, 1, vote.3[0], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 40), (vote.3[1], 20)]));
+			assert_eq!(System::events().last().unwrap().event,
+				Event::voting(voting::RawEvent::VoteCompleted(1, vec![(vote.3[0], 40), (vote.3[1], 20)], VoteResult::Passed))
+			);
+		});
+	}
+
 	#[test]
 	fn transition_to_commit_should_work() {
 		with_externalities(&mut new_test_ext(), || {
@@ -545,11 +675,7 @@ mod tests {
 			assert_ok!(advance_stage_as_initiator(public, 1));
 			let public2 = get_test_key_2();
 			let secret = SECRET;
-			let mut buf = Vec::new();
-			buf.extend_from_slice(&public2.encode());
-			buf.extend_from_slice(&secret);
-			buf.extend_from_slice(&vote.3[0]);
-			let commit_hash: [u8; 32] = BlakeTwo256::hash_of(&buf).into();
+			let commit_hash = commitment::build::<BlakeTwo256, _>(1, &public2, &secret, &[vote.3[0]]);
 			assert_ok!(commit(public2, 1, commit_hash));
 			assert_eq!(
 				Voting::vote_records(1).unwrap().commitments,
@@ -570,11 +696,7 @@ mod tests {
 			assert_ok!(advance_stage_as_initiator(public, 1));
 			let public2 = get_test_key_2();
 			let secret = SECRET;
-			let mut buf = Vec::new();
-			buf.extend_from_slice(&public2.encode());
-			buf.extend_from_slice(&secret);
-			buf.extend_from_slice(&vote.3[0]);
-			let commit_hash: [u8; 32] = BlakeTwo256::hash_of(&buf).into();
+			let commit_hash = commitment::build::<BlakeTwo256, _>(1, &public2, &secret, &[vote.3[0]]);
 			assert_ok!(commit(public2, 1, commit_hash));
 			assert_eq!(
 				Voting::vote_records(1).unwrap().commitments,
@@ -602,7 +724,7 @@ mod tests {
 				},
 				EventRecord {
 					phase: Phase::ApplyExtrinsic(0),
-					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vote.3[0]))
+					event: Event::voting(voting::RawEvent::VoteRevealed(1, public2, vec![vote.3[0]]))
 				}
 			]);
 		});
@@ -640,6 +762,1310 @@ mod tests {
 			assert_ok!(reveal(users[4], 1, vote.3[1], None));
 			assert_ok!(reveal(users[5], 1, vote.3[0], None));
 			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 3), (vote.3[1], 3)]));
+		});
+	}
+
+	#[test]
+	fn delegation_walk_should_be_bounded() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let delegators = voting::MAX_DELEGATION_WALK as u64 + 10;
+			for who in 100..(100 + delegators) {
+				assert_ok!(delegate_to(who, 1));
+			}
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_eq!(Voting::turnout_of(1), 1 + voting::MAX_DELEGATION_WALK as u64);
+		});
+	}
+
+	#[test]
+	fn delegated_one_coin_tally_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// 2 --> 3 --> 4, only 4 votes
+			assert_ok!(delegate_to(2, 3));
+			assert_ok!(delegate_to(3, 4));
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(4, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 0), (vote.3[1], 90)]));
+		});
+	}
+
+	#[test]
+	fn redelegated_weight_should_count_once() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// 2 --> 4, then 2 --> 5, then back to 2 --> 4
+			assert_ok!(delegate_to(2, 4));
+			assert_ok!(delegate_to(2, 5));
+			assert_ok!(delegate_to(2, 4));
+			// Backlinks duplicated before re-delegation removed them are skipped too
+			<delegation::DelegatesTo<Test>>::insert(4, vec![2, 2]);
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(4, 1, vote.3[0], None));
+			assert_ok!(reveal(5, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 60), (vote.3[1], 50)]));
+		});
+	}
+
+	#[test]
+	fn redelegating_after_reveal_should_move_weight() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(delegate_to(2, 4));
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(4, 1, vote.3[0], None));
+			assert_ok!(reveal(5, 1, vote.3[1], None));
+			assert_eq!(Voting::counted_in(&(1, 2)), Some(4));
+			assert_eq!(Voting::turnout_of(1), 110);
+
+			// 2's weight moves from 4 to 5 instead of being counted for both
+			assert_ok!(delegate_to(2, 5));
+			assert_eq!(Voting::counted_in(&(1, 2)), Some(5));
+			assert_eq!(Voting::counted_delegators_of(&(1, 4)), vec![]);
+			assert_eq!(Voting::turnout_of(1), 110);
+			assert_eq!(Voting::compute_tally(&Voting::vote_of(1).unwrap()), vec![(vote.3[0], 40), (vote.3[1], 70)]);
+
+			assert_ok!(Delegation::undelegate_from(Origin::signed(2), 5));
+			assert_eq!(Voting::counted_in(&(1, 2)), None);
+			assert_eq!(Voting::counted_votes_of(&2), vec![]);
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 40), (vote.3[1], 50)]));
+		});
+	}
+
+	#[test]
+	fn counted_delegator_should_not_transfer_weight_to_another_voter() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			assert_ok!(delegate_to(2, 4));
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(4, 1, vote.3[0], None));
+			// 2's weight is fixed and locked once counted for 4
+			assert_eq!(Voting::weight_snapshot_of((1, 2)), Some(20));
+			assert!(transfer(2, 3, 20).is_err());
+			assert_ok!(reveal(3, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 60), (vote.3[1], 30)]));
+
+			assert_ok!(Voting::unlock(Origin::signed(2), 1));
+			assert_ok!(transfer(2, 3, 20));
+		});
+	}
+
+	#[test]
+	fn direct_vote_should_override_delegate() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// 2 --> 3 --> 4, 2 and 4 vote directly
+			assert_ok!(delegate_to(2, 3));
+			assert_ok!(delegate_to(3, 4));
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(reveal(4, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 20), (vote.3[1], 70)]));
+		});
+	}
+
+	#[test]
+	fn one_coin_reveal_should_lock_weight() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_eq!(Voting::weight_snapshot_of((1, 2)), Some(20));
+			assert!(transfer(2, 3, 15).is_err());
+			assert_err!(Voting::unlock(Origin::signed(2), 1), "Vote has not concluded");
+
+			// The weight stays locked until the voter unlocks it
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert!(transfer(2, 3, 15).is_err());
+			assert_err!(Voting::unlock(Origin::signed(3), 1), "No weight locked for this vote");
+			assert_ok!(Voting::unlock(Origin::signed(2), 1));
+			assert_eq!(Voting::weight_snapshot_of((1, 2)), None);
+			assert_ok!(transfer(2, 3, 15));
+		});
+	}
+
+	#[test]
+	fn locked_weight_should_pay_fees() {
+		with_externalities(&mut new_test_ext_with_fee(2), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Balances::make_payment(&2, 0));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_eq!(Voting::weight_snapshot_of((1, 2)), Some(18));
+
+			// Fees are paid from the locked balance, so the voter can still re-vote
+			assert_ok!(Balances::make_payment(&2, 0));
+			assert_ok!(reveal(2, 1, vote.3[1], None));
+			assert_eq!(Balances::free_balance(&2), 16);
+			assert!(transfer(2, 3, 1).is_err());
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 0), (vote.3[1], 18)]));
+		});
+	}
+
+	#[test]
+	fn lock_ids_should_not_collide() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			// Vote ids that agree in their low 32 bits hold separate locks
+			<voting::VoteRecordCount<Test>>::put(1 << 32);
+			assert_eq!(Ok((1 << 32) + 1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			<voting::VoteRecordCount<Test>>::put(0);
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, (1 << 32) + 1));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(reveal(2, (1 << 32) + 1, vote.3[0], None));
+
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Voting::unlock(Origin::signed(2), 1));
+			assert!(transfer(2, 3, 15).is_err());
+			assert_ok!(advance_stage_as_initiator(public, (1 << 32) + 1));
+			assert_ok!(Voting::unlock(Origin::signed(2), (1 << 32) + 1));
+			assert_ok!(transfer(2, 3, 15));
+		});
+	}
+
+	#[test]
+	fn transfer_and_revote_should_not_double_count() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(3, 1, vote.3[0], None));
+			// funds received after revealing do not add to the fixed weight
+			assert_ok!(transfer(4, 3, 40));
+			assert_ok!(reveal(4, 1, vote.3[0], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 30), (vote.3[1], 0)]));
+		});
+	}
+
+	#[test]
+	fn changing_reveal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let public2 = get_test_key_2();
+			assert_ok!(reveal(public2, 1, vote.3[0], None));
+			assert_ok!(reveal(public2, 1, vote.3[1], None));
+			assert_eq!(
+				Voting::vote_records(1).unwrap().reveals,
+				vec![(public2, vec![vote.3[1]])]
+			);
+			assert_eq!(System::events().last().unwrap().event,
+				Event::voting(voting::RawEvent::VoteChanged(1, public2, vec![vote.3[1]]))
+			);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 0), (vote.3[1], 1)]));
+		});
+	}
+
+	#[test]
+	fn changing_commit_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let public2 = get_test_key_2();
+			let secret = SECRET;
+			let mut commit_hashes = vec![];
+			for outcome in vote.3.iter() {
+				let commit_hash = commitment::build::<BlakeTwo256, _>(1, &public2, &secret, &[*outcome]);
+				commit_hashes.push(commit_hash);
+			}
+			assert_ok!(commit(public2, 1, commit_hashes[0]));
+			assert_ok!(commit(public2, 1, commit_hashes[1]));
+			assert_eq!(
+				Voting::vote_records(1).unwrap().commitments,
+				vec![(public2, commit_hashes[1])]
+			);
+			assert_eq!(System::events().last().unwrap().event,
+				Event::voting(voting::RawEvent::CommitmentChanged(1, public2))
+			);
+
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(reveal(public2, 1, vote.3[0], Some(secret)), "Commitments do not match");
+			assert_ok!(reveal(public2, 1, vote.3[1], Some(secret)));
+		});
+	}
+
+	#[test]
+	fn reveal_bond_should_be_returned_or_slashed() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_reveal_bond(1, 5));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(Voting::set_reveal_bond(1, 5), "Vote is not in prevoting stage");
+
+			let secret = SECRET;
+			let mut commit_hashes = vec![];
+			for who in [2_u64, 3_u64].iter() {
+				let commit_hash = commitment::build::<BlakeTwo256, _>(1, who, &secret, &[vote.3[0]]);
+				assert_ok!(commit(*who, 1, commit_hash));
+				commit_hashes.push(commit_hash);
+			}
+			// changing a commitment does not reserve the bond again
+			assert_ok!(commit(2, 1, commit_hashes[0]));
+			assert_eq!(Balances::reserved_balance(2), 5);
+			assert_eq!(Balances::reserved_balance(3), 5);
+
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(2, 1, vote.3[0], Some(secret)));
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 20);
+
+			// The unrevealed bond is slashed when the vote is pruned
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Balances::reserved_balance(3), 5);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 25);
+			assert!(System::events().iter().any(|e| e.event ==
+				Event::voting(voting::RawEvent::RevealBondSlashed(1, 3, 5))
+			));
+		});
+	}
+
+	#[test]
+	fn reveal_bond_on_public_vote_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_err!(Voting::set_reveal_bond(1, 5), "Reveal bonds require a commit-reveal vote");
+		});
+	}
+
+	#[test]
+	fn create_ranked_choice_vote_with_binary_options_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_ranked_choice_vote();
+			let binary_vote = generate_1p1v_public_binary_vote();
+			assert_err!(create_vote(public, vote.0, vote.1, vote.2, &binary_vote.3), "Invalid ranked choice outcomes");
+		});
+	}
+
+	#[test]
+	fn invalid_ballots_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let binary_vote = generate_1p1v_public_binary_vote();
+			let vote = generate_1p1v_ranked_choice_vote();
+			assert_eq!(Ok(1), create_vote(public, binary_vote.0, binary_vote.1, binary_vote.2, &binary_vote.3));
+			assert_eq!(Ok(2), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			assert_err!(reveal_ballot(2, 1, &binary_vote.3, None), "Vote must have exactly one outcome");
+			assert_err!(reveal_ballot(2, 2, &[], None), "Vote outcome is not valid");
+			assert_err!(reveal_ballot(2, 2, &[vote.3[0], SECRET], None), "Vote outcome is not valid");
+			assert_err!(reveal_ballot(2, 2, &[vote.3[0], vote.3[1], vote.3[0]], None), "Vote outcomes must be unique");
+		});
+	}
+
+	#[test]
+	fn instant_runoff_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_ranked_choice_vote();
+			let (a, b, c, d) = (vote.3[0], vote.3[1], vote.3[2], vote.3[3]);
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_ballot(1, 1, &[a, b], None));
+			assert_ok!(reveal_ballot(2, 1, &[a], None));
+			assert_ok!(reveal_ballot(3, 1, &[b, c], None));
+			assert_ok!(reveal_ballot(4, 1, &[c, b], None));
+			assert_ok!(reveal_ballot(5, 1, &[d, c], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+
+			// d is eliminated first (tied with b and c but listed last), then b,
+			// after which c holds a majority
+			let rounds = vec![
+				vec![(a, 2), (b, 1), (c, 1), (d, 1)],
+				vec![(a, 2), (b, 1), (c, 2), (d, 0)],
+				vec![(a, 2), (b, 0), (c, 3), (d, 0)],
+			];
+			assert_eq!(Voting::runoff_rounds_of(1), rounds);
+			assert_eq!(Voting::tally_of(1), Some(rounds[2].clone()));
+
+			// Counted rankings are removed with the vote
+			assert_eq!(Voting::ranked_ballot_count_of(1), 5);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Voting::ranked_ballot_count_of(1), 0);
+			assert_eq!(Voting::ranked_ballot_weight_of(&(1, vec![a, b])), 0);
+		});
+	}
+
+	#[test]
+	fn ranked_choice_vote_with_too_many_outcomes_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let outcomes: Vec<[u8; 32]> = (0..(voting::MAX_RANKED_OUTCOMES as u8 + 1)).map(|i| [i + 1; 32]).collect();
+			assert_err!(
+				create_vote(public, VoteType::RankedChoice, false, TallyType::OnePerson, &outcomes),
+				"Invalid ranked choice outcomes"
+			);
+			assert_eq!(
+				Ok(1),
+				create_vote(public, VoteType::RankedChoice, false, TallyType::OnePerson, &outcomes[1..])
+			);
+		});
+	}
+
+	#[test]
+	fn approval_tally_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_multi_vote();
+			let (a, b, c, d) = (vote.3[0], vote.3[1], vote.3[2], vote.3[3]);
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_ballot(1, 1, &[c, a], None));
+			assert_ok!(reveal_ballot(2, 1, &[c], None));
+			assert_ok!(reveal_ballot(3, 1, &[b, a], None));
+			assert_err!(reveal_ballot(4, 1, &[b, b], None), "Vote outcomes must be unique");
+			assert_eq!(
+				Voting::vote_records(1).unwrap().reveals[0],
+				(1, vec![a, c])
+			);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(a, 40), (b, 30), (c, 30), (d, 0)]));
+		});
+	}
+
+	#[test]
+	fn approval_commit_reveal_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_multi_vote();
+			let (a, c) = (vote.3[0], vote.3[2]);
+			assert_eq!(Ok(1), create_vote(public, vote.0, true, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let public2 = get_test_key_2();
+			let secret = SECRET;
+			// the commitment covers the approved outcomes in ascending order
+			let commit_hash = commitment::build::<BlakeTwo256, _>(1, &public2, &secret, &[a, c]);
+			assert_ok!(commit(public2, 1, commit_hash));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_ballot(public2, 1, &[c, a], Some(secret)));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(a, 1), (vote.3[1], 0), (c, 1), (vote.3[3], 0)]));
+		});
+	}
+
+	#[test]
+	fn quadratic_tally_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::Quadratic, &vote.3));
+			assert_err!(Voting::purchase_votes(Origin::signed(2), 1, 1), "Vote is not in commit or voting stage");
+			assert_ok!(advance_stage_as_initiator(public, 1));
+
+			assert_err!(Voting::purchase_votes(Origin::signed(2), 1, 5), "Not enough currency to purchase votes");
+			assert_ok!(Voting::purchase_votes(Origin::signed(2), 1, 4));
+			assert_eq!(Balances::reserved_balance(2), 16);
+			assert_ok!(Voting::purchase_votes(Origin::signed(2), 1, 3));
+			assert_eq!(Balances::reserved_balance(2), 9);
+			assert_ok!(Voting::purchase_votes(Origin::signed(3), 1, 5));
+			assert_eq!(Balances::reserved_balance(3), 25);
+
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(reveal(3, 1, vote.3[1], None));
+			assert_err!(reveal(4, 1, vote.3[0], None), "No votes purchased");
+			// Purchases are frozen once the voter has revealed
+			assert_err!(Voting::purchase_votes(Origin::signed(2), 1, 0), "Votes cannot be purchased after revealing");
+			assert_eq!(Balances::reserved_balance(2), 9);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 3), (vote.3[1], 5)]));
+
+			// the cost of purchased votes is burned when the vote is pruned
+			assert_eq!(Balances::reserved_balance(2), 9);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Voting::purchased_votes_of(&(1, 2)), 0);
+			assert_eq!(Voting::vote_buyer_count_of(1), 0);
+			assert_eq!(Balances::reserved_balance(2), 0);
+			assert_eq!(Balances::free_balance(2), 11);
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 5);
+		});
+	}
+
+	#[test]
+	fn purchase_votes_for_non_quadratic_vote_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(Voting::purchase_votes(Origin::signed(2), 1, 1), "Votes can only be purchased for quadratic votes");
+		});
+	}
+
+	#[test]
+	fn split_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			let (yes, no) = (vote.3[0], vote.3[1]);
+			assert_ok!(delegate_to(4, 5));
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+
+			assert_err!(reveal_split(3, 1, &[(yes, 20), (no, 11)], None), "Split vote exceeds voter weight");
+			assert_err!(reveal_split(3, 1, &[(yes, 10), (yes, 10)], None), "Vote outcomes must be unique");
+			assert_ok!(reveal_split(5, 1, &[(yes, 30), (no, 20)], None));
+			assert_eq!(Voting::split_vote_of((1, 5)), Some(vec![(yes, 30), (no, 20)]));
+			assert_eq!(
+				Voting::vote_records(1).unwrap().reveals,
+				vec![(5, vec![yes, no])]
+			);
+			assert_ok!(reveal(2, 1, yes, None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			// 4's delegated weight of 40 is split 30:20 between the outcomes
+			assert_eq!(Voting::tally_of(1), Some(vec![(yes, 20 + 30 + 24), (no, 20 + 16)]));
+		});
+	}
+
+	#[test]
+	fn split_vote_replaced_by_single_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			let (yes, no) = (vote.3[0], vote.3[1]);
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_split(3, 1, &[(yes, 10), (no, 20)], None));
+			assert_ok!(reveal(3, 1, no, None));
+			assert_eq!(Voting::split_vote_of((1, 3)), None);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(yes, 0), (no, 30)]));
+		});
+	}
+
+	#[test]
+	fn split_vote_for_one_person_vote_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(reveal_split(3, 1, &[(vote.3[0], 1)], None), "Split votes require a one coin vote");
+		});
+	}
+
+	#[test]
+	fn scheduled_vote_should_advance_automatically() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (2, 3)));
+			assert_eq!(Voting::stage_lengths_of(1), Some((2, 3)));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::stage_transitions_at(3), vec![(1, VoteStage::Commit)]);
+			assert_err!(advance_stage_as_initiator(public, 1), "Vote stages advance automatically");
+
+			finalize_block(2);
+			assert_eq!(Voting::vote_records(1).unwrap().data.stage, VoteStage::Commit);
+			finalize_block(3);
+			assert_eq!(Voting::vote_records(1).unwrap().data.stage, VoteStage::Voting);
+			assert_eq!(Voting::stage_transitions_at(3), vec![]);
+			assert_eq!(Voting::stage_transitions_at(6), vec![(1, VoteStage::Voting)]);
+			finalize_block(6);
+			assert_eq!(Voting::vote_records(1).unwrap().data.stage, VoteStage::Completed);
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 0), (vote.3[1], 0)]));
+		});
+	}
+
+	#[test]
+	fn scheduled_transition_should_skip_votes_advanced_elsewhere() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (2, 3)));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::stage_transitions_at(4), vec![(1, VoteStage::Voting)]);
+			assert_ok!(Voting::advance_stage(1));
+			finalize_block(4);
+			assert_eq!(Voting::vote_records(1).unwrap().data.stage, VoteStage::Completed);
+		});
+	}
+
+	#[test]
+	fn create_vote_with_zero_stage_length_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_err!(
+				create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (0, 3)),
+				"Stage lengths must be positive"
+			);
+			assert_err!(
+				create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (2, 0)),
+				"Stage lengths must be positive"
+			);
+			// Votes without a commit stage need no commit length
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (0, 3)));
+			assert_err!(
+				create_scheduled_vote(public, vote.0, vote.1, vote.2, &vote.3, (0, 0)),
+				"Stage lengths must be positive"
+			);
+		});
+	}
+
+	#[test]
+	fn vote_without_passing_rules_should_need_simple_majority() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::result_of(1), Some(VoteResult::Failed));
+		});
+	}
+
+	#[test]
+	fn vote_below_quorum_should_have_no_quorum() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_passing_rules(1, Some(3), None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 2), (vote.3[1], 0)]));
+			assert_eq!(Voting::result_of(1), Some(VoteResult::NoQuorum));
+		});
+	}
+
+	#[test]
+	fn super_majority_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			let two_thirds = Some(VoteThreshold::SuperMajority(2, 3));
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_eq!(Ok(2), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_passing_rules(1, Some(3), two_thirds));
+			assert_ok!(Voting::set_passing_rules(2, Some(3), two_thirds));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			// 2/3 approve the first vote, 3/5 approve the second
+			for (who, choice) in [(1, 0), (2, 0), (3, 1)].iter() {
+				assert_ok!(reveal(*who, 1, vote.3[*choice], None));
+			}
+			for (who, choice) in [(1, 0), (2, 0), (3, 0), (4, 1), (5, 1)].iter() {
+				assert_ok!(reveal(*who, 2, vote.3[*choice], None));
+			}
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			assert_eq!(Voting::result_of(1), Some(VoteResult::Passed));
+			assert_eq!(Voting::result_of(2), Some(VoteResult::Failed));
+		});
+	}
+
+	#[test]
+	fn multi_option_approvals_should_count_once_against() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_multi_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_passing_rules(1, None, Some(VoteThreshold::SuperMajority(2, 3))));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_ballot(1, 1, &[vote.3[0], vote.3[1]], None));
+			assert_ok!(reveal_ballot(2, 1, &[vote.3[0]], None));
+			assert_ok!(reveal_ballot(3, 1, &[vote.3[2]], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			// 2 of 3 voters approve the leading outcome, voter 1 only counts for it
+			assert_eq!(Voting::result_of(1), Some(VoteResult::Passed));
+		});
+	}
+
+	#[test]
+	fn adaptive_quorum_biasing_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			let thresholds = [VoteThreshold::SuperMajorityApprove, VoteThreshold::SuperMajorityAgainst];
+			for (i, threshold) in thresholds.iter().enumerate() {
+				let id = i as u64 + 1;
+				assert_eq!(Ok(id), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+				assert_ok!(Voting::set_passing_rules(id, None, Some(*threshold)));
+				assert_ok!(advance_stage_as_initiator(public, id));
+				assert_ok!(reveal(4, id, vote.3[0], None));
+				assert_ok!(reveal(3, id, vote.3[1], None));
+				assert_ok!(advance_stage_as_initiator(public, id));
+			}
+			// With 70 of 280 turning out, 40 to 30 is too slim to approve but
+			// also too slim to reject
+			assert_eq!(Voting::result_of(1), Some(VoteResult::Failed));
+			assert_eq!(Voting::result_of(2), Some(VoteResult::Passed));
+		});
+	}
+
+	#[test]
+	fn invalid_passing_rules_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_err!(
+				Voting::set_passing_rules(1, None, Some(VoteThreshold::SuperMajority(3, 2))),
+				"Invalid passing threshold"
+			);
+			// One person votes have no known electorate to adapt to
+			assert_err!(
+				Voting::set_passing_rules(1, None, Some(VoteThreshold::SuperMajorityApprove)),
+				"Adaptive thresholds require a one coin vote"
+			);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(Voting::set_passing_rules(1, Some(1), None), "Vote is not in prevoting stage");
+		});
+	}
+
+	#[test]
+	fn identity_gated_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_identity_requirement(1, vec![]));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(reveal(4, 1, vote.3[0], None), "Voter does not hold a required identity");
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[1], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 1), (vote.3[1], 1)]));
+		});
+	}
+
+	#[test]
+	fn identity_gated_vote_should_check_identity_types() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_identity_requirement(1, vec![b"twitter".to_vec()]));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let commit_value = SECRET;
+			assert_err!(commit(4, 1, commit_value), "Voter does not hold a required identity");
+			assert_err!(commit(public, 1, commit_value), "Voter does not hold a required identity");
+		});
+	}
+
+	#[test]
+	fn identity_gated_vote_should_ignore_unverified_delegators() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(Voting::set_identity_requirement(1, vec![b"github".to_vec()]));
+			// 2 is verified, 4 and 5 are not
+			assert_ok!(delegate_to(2, 1));
+			assert_ok!(delegate_to(4, 1));
+			assert_ok!(delegate_to(5, 4));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 2), (vote.3[1], 0)]));
+		});
+	}
+
+	#[test]
+	fn revealing_again_should_not_index_voter_twice() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(1, 1, vote.3[0], None));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_ok!(reveal(1, 1, vote.3[1], None));
+			assert_eq!(Voting::voter_count_of(1), 2);
+			assert_eq!(Voting::voters_of(1), vec![1, 2]);
+			assert_eq!(Voting::reveal_of(&(1, 1)), Some(vec![vote.3[1]]));
+		});
+	}
+
+	#[test]
+	fn legacy_vote_records_should_migrate() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			<voting::VoteRecords<Test>>::insert(1, voting::LegacyVoteRecord {
+				id: 1,
+				commitments: vec![(1, SECRET), (2, SECRET)],
+				reveals: vec![(2, vote.3[1])],
+				data: voting::LegacyVoteData {
+					initiator: public,
+					stage: VoteStage::Voting,
+					vote_type: vote.0,
+					tally_type: vote.2,
+					is_commit_reveal: vote.1,
+				},
+				outcomes: vote.3.to_vec(),
+			});
+			<voting::VoteRecordCount<Test>>::put(1);
+			assert_eq!(Voting::storage_version(), 0);
+			<Voting as OnInitialize<u64>>::on_initialize(1);
+			assert_eq!(Voting::storage_version(), 2);
+
+			let mut record = make_record(1, public, vote.0, vote.1, vote.2, &vote.3, VoteStage::Voting);
+			record.commitments = vec![(1, SECRET), (2, SECRET)];
+			record.reveals = vec![(2, vec![vote.3[1]])];
+			assert_eq!(Voting::vote_records(1), Some(record));
+			assert_eq!(Voting::committer_count_of(1), 2);
+			assert_eq!(Voting::voter_count_of(1), 1);
+			assert_eq!(<voting::VoteRecords<Test>>::get(1), None);
+			assert_eq!(Voting::compute_tally(&Voting::vote_of(1).unwrap()), vec![(vote.3[0], 0), (vote.3[1], 1)]);
+		});
+	}
+
+	#[test]
+	fn legacy_commitments_should_be_accepted_for_old_votes() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			// Upgrading a chain at storage version 1 marks vote 1 as legacy
+			<voting::StorageVersion<Test>>::put(1);
+			<Voting as OnInitialize<u64>>::on_initialize(1);
+			assert_eq!(Voting::storage_version(), 2);
+			assert_eq!(Ok(2), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+
+			let legacy_commit = |who: u64| {
+				let mut buf = Vec::new();
+				buf.extend_from_slice(&who.encode());
+				buf.extend_from_slice(&SECRET.encode());
+				buf.extend_from_slice(&vote.3[0]);
+				let mut commit = [0u8; 32];
+				commit.copy_from_slice(BlakeTwo256::hash_of(&buf).as_ref());
+				commit
+			};
+			for id in [1_u64, 2_u64].iter() {
+				assert_ok!(advance_stage_as_initiator(public, *id));
+				assert_ok!(commit(1, *id, legacy_commit(1)));
+				assert_ok!(advance_stage_as_initiator(public, *id));
+			}
+			assert_ok!(reveal(1, 1, vote.3[0], Some(SECRET)));
+			assert_err!(reveal(1, 2, vote.3[0], Some(SECRET)), "Commitments do not match");
+		});
+	}
+
+	#[test]
+	fn legacy_vote_records_should_migrate_in_batches() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			for id in [1_u64, 25_u64].iter() {
+				<voting::VoteRecords<Test>>::insert(*id, voting::LegacyVoteRecord {
+					id: *id,
+					commitments: vec![],
+					reveals: vec![],
+					data: voting::LegacyVoteData {
+						initiator: public,
+						stage: VoteStage::Completed,
+						vote_type: vote.0,
+						tally_type: vote.2,
+						is_commit_reveal: vote.1,
+					},
+					outcomes: vote.3.to_vec(),
+				});
+			}
+			<voting::VoteRecordCount<Test>>::put(25);
+			<Voting as OnInitialize<u64>>::on_initialize(1);
+			assert_eq!(Voting::storage_version(), 0);
+			assert!(Voting::vote_of(1).is_some());
+			assert!(Voting::vote_of(25).is_none());
+
+			<Voting as OnInitialize<u64>>::on_initialize(2);
+			assert_eq!(Voting::storage_version(), 2);
+			assert!(Voting::vote_of(25).is_some());
+			assert_eq!(<voting::MigrationCursor<Test>>::get(), None);
+		});
+	}
+
+	#[test]
+	fn running_tally_should_follow_reveals() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			// 2 --> 3
+			assert_ok!(delegate_to(2, 3));
+
+			let creator = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(creator, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(creator, 1));
+			assert_ok!(reveal(3, 1, vote.3[0], None));
+			assert_eq!(Voting::compute_tally(&Voting::vote_of(1).unwrap()), vec![(vote.3[0], 50), (vote.3[1], 0)]);
+			assert_eq!(Voting::turnout_of(1), 50);
+
+			// Changing a vote moves the voter's weight
+			assert_ok!(reveal(3, 1, vote.3[1], None));
+			assert_eq!(Voting::compute_tally(&Voting::vote_of(1).unwrap()), vec![(vote.3[0], 0), (vote.3[1], 50)]);
+
+			// A delegator voting directly takes their weight back from the delegate
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			assert_eq!(Voting::compute_tally(&Voting::vote_of(1).unwrap()), vec![(vote.3[0], 20), (vote.3[1], 30)]);
+			assert_eq!(Voting::turnout_of(1), 50);
+		});
+	}
+
+	#[test]
+	fn cancel_vote_should_release_bonds_and_locks() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(Voting::set_reveal_bond(1, 5));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+
+			let secret = SECRET;
+			for who in [2_u64, 3_u64].iter() {
+				let commit_hash = commitment::build::<BlakeTwo256, _>(1, who, &secret, &[vote.3[0]]);
+				assert_ok!(commit(*who, 1, commit_hash));
+			}
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(2, 1, vote.3[0], Some(secret)));
+			assert!(transfer(2, 4, 1).is_err());
+
+			assert_err!(Voting::cancel_vote_as_initiator(Origin::signed(2), 1), "Invalid cancel attempt by non-owner");
+			assert_ok!(Voting::cancel_vote_as_initiator(Origin::signed(public), 1));
+			assert_eq!(Voting::vote_of(1).unwrap().data.stage, VoteStage::Cancelled);
+			assert_eq!(System::events().last().unwrap().event, Event::voting(voting::RawEvent::VoteCancelled(1)));
+			assert_err!(advance_stage_as_initiator(public, 1), "Vote was cancelled");
+			assert_err!(Voting::cancel_vote_as_initiator(Origin::signed(public), 1), "Vote was cancelled");
+			assert_eq!(Voting::tally_of(1), None);
+
+			// the revealed weight can be unlocked, and pruning returns the unrevealed bond
+			assert_ok!(Voting::unlock(Origin::signed(2), 1));
+			assert_ok!(transfer(2, 4, 1));
+			assert_eq!(Balances::reserved_balance(3), 5);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Balances::reserved_balance(3), 0);
+			assert_eq!(Balances::free_balance(3), 30);
+		});
+	}
+
+	#[test]
+	fn force_cancel_vote_should_refund_purchased_votes() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::Quadratic, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Voting::purchase_votes(Origin::signed(2), 1, 3));
+			assert_eq!(Balances::free_balance(2), 11);
+
+			assert!(Voting::force_cancel_vote(Origin::signed(public), 1).is_err());
+			assert_ok!(Voting::force_cancel_vote(Origin::ROOT, 1));
+			assert_eq!(Balances::free_balance(2), 11);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Balances::free_balance(2), 20);
+			assert_eq!(Balances::reserved_balance(2), 0);
+		});
+	}
+
+	#[test]
+	fn cancel_completed_vote_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_err!(Voting::cancel_vote_as_initiator(Origin::signed(public), 1), "Vote already completed");
+		});
+	}
+
+	#[test]
+	fn conviction_should_multiply_weight_and_lock() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::ConvictionPeriod<Test>>::put(4);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Voting::reveal_with_conviction(Origin::signed(1), 1, vec![vote.3[0]], None, 2));
+			assert_ok!(reveal(2, 1, vote.3[1], None));
+			// the voting period is 4 blocks, so a conviction of 2 locks for 8
+			System::set_block_number(5);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 30), (vote.3[1], 20)]));
+			assert_eq!(Voting::unlock_at(1, &1), Some(13));
+			assert_eq!(Voting::unlock_at(1, &2), Some(5));
+			assert_err!(Voting::unlock(Origin::signed(1), 1), "Conviction lock has not expired");
+			assert!(transfer(1, 3, 1).is_err());
+			assert_ok!(Voting::unlock(Origin::signed(2), 1));
+			assert_ok!(transfer(2, 3, 1));
+
+			// The conviction lock outlives the pruned vote
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Voting::conviction_lock_of(&(1, 1)), Some((10, 13)));
+			System::set_block_number(12);
+			assert_err!(Voting::unlock(Origin::signed(1), 1), "Conviction lock has not expired");
+			System::set_block_number(13);
+			assert_ok!(Voting::unlock(Origin::signed(1), 1));
+			assert_eq!(Voting::conviction_lock_of(&(1, 1)), None);
+			assert_ok!(transfer(1, 3, 1));
+			assert_err!(Voting::unlock(Origin::signed(1), 1), "No weight locked for this vote");
+		});
+	}
+
+	#[test]
+	fn conviction_should_not_count_towards_turnout() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::ConvictionPeriod<Test>>::put(4);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(Voting::set_passing_rules(1, Some(40), None));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Voting::reveal_with_conviction(Origin::signed(1), 1, vec![vote.3[0]], None, 2));
+			assert_ok!(reveal(2, 1, vote.3[0], None));
+			// 30 turned out, tallied as 50 with 1's conviction
+			assert_eq!(Voting::turnout_of(1), 30);
+			assert_eq!(Voting::counted_total_of(1), 50);
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 50), (vote.3[1], 0)]));
+			assert_eq!(Voting::result_of(1), Some(VoteResult::NoQuorum));
+		});
+	}
+
+	#[test]
+	fn invalid_conviction_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_eq!(Ok(2), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			assert_err!(
+				Voting::reveal_with_conviction(Origin::signed(1), 1, vec![vote.3[0]], None, 1),
+				"Conviction requires a one coin vote"
+			);
+			assert_err!(
+				Voting::reveal_with_conviction(Origin::signed(1), 2, vec![vote.3[0]], None, voting::MAX_CONVICTION + 1),
+				"Conviction is too high"
+			);
+			// Without stage lengths or a configured period, conviction locks would be empty
+			assert_err!(
+				Voting::reveal_with_conviction(Origin::signed(1), 2, vec![vote.3[0]], None, 1),
+				"Conviction requires a voting period"
+			);
+		});
+	}
+
+	#[test]
+	fn conviction_should_lock_for_voting_stage_length() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::ConvictionPeriod<Test>>::put(4);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			<voting::StageLengths<Test>>::insert(1, (0, 3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(Voting::reveal_with_conviction(Origin::signed(1), 1, vec![vote.3[0]], None, 2));
+			// Completing early still locks for two voting stages of 3 blocks
+			System::set_block_number(2);
+			assert_ok!(Voting::advance_stage(1));
+			assert_eq!(Voting::unlock_at(1, &1), Some(8));
+			assert!(transfer(1, 3, 1).is_err());
+		});
+	}
+
+	#[test]
+	fn create_vote_with_labels_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let (outcomes, labels) = voting::outcomes_from_labels(&["Yes", "No"]);
+			assert_eq!(outcomes[0], voting::outcome_from_label("Yes"));
+			assert_eq!(
+				Ok(1),
+				Voting::create_vote(public, VoteType::Binary, false, TallyType::OnePerson, outcomes.clone(), labels, None)
+			);
+			assert_eq!(Voting::label_of(1, &outcomes[1]), Some(b"No".to_vec()));
+			assert_eq!(Voting::vote_records(1).unwrap().labels, vec![b"Yes".to_vec(), b"No".to_vec()]);
+		});
+	}
+
+	#[test]
+	fn create_vote_with_invalid_labels_should_not_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			let create = |labels: Vec<Vec<u8>>| Voting::create_vote(
+				public, vote.0, vote.1, vote.2, vote.3.to_vec(), labels, None
+			);
+			assert_err!(create(vec![b"Yes".to_vec()]), "Outcome labels do not match outcomes");
+			assert_err!(create(vec![b"Yes".to_vec(), vec![b'n'; 65]]), "Outcome label is too long");
+			assert_err!(create(vec![b"Yes".to_vec(), vec![0xff, 0xfe]]), "Outcome label is not valid UTF-8");
+			assert_eq!(Voting::vote_record_count(), 0);
+		});
+	}
+
+	#[test]
+	fn commitment_should_follow_documented_format() {
+		let who = get_test_key_2();
+		let vote = generate_1p1v_commit_reveal_binary_vote();
+		let mut buf = Vec::new();
+		buf.extend_from_slice(voting::COMMITMENT_PREFIX);
+		buf.extend_from_slice(&7_u64.encode());
+		buf.extend_from_slice(&who.encode());
+		buf.extend_from_slice(&SECRET);
+		buf.extend_from_slice(&vote.3[0]);
+		let expected: [u8; 32] = BlakeTwo256::hash(&buf).into();
+		let commit_hash = commitment::build::<BlakeTwo256, _>(7, &who, &SECRET, &[vote.3[0]]);
+		assert_eq!(commit_hash, expected);
+		assert!(commitment::verify::<BlakeTwo256, _>(&commit_hash, 7, &who, &SECRET, &[vote.3[0]]));
+		assert!(!commitment::verify::<BlakeTwo256, _>(&commit_hash, 7, &who, &SECRET, &[vote.3[1]]));
+		assert!(!commitment::verify::<BlakeTwo256, _>(&commit_hash, 8, &who, &SECRET, &[vote.3[0]]));
+		assert_ne!(commitment::generate_secret(), commitment::generate_secret());
+	}
+
+	#[test]
+	fn commitment_should_not_be_replayed_across_votes() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_eq!(Ok(2), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			let secret = commitment::generate_secret();
+			let commit_hash = commitment::build::<BlakeTwo256, _>(1, &2_u64, &secret, &[vote.3[0]]);
+			assert_ok!(commit(2, 1, commit_hash));
+			assert_ok!(commit(2, 2, commit_hash));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(advance_stage_as_initiator(public, 2));
+			assert_ok!(reveal(2, 1, vote.3[0], Some(secret)));
+			assert_err!(reveal(2, 2, vote.3[0], Some(secret)), "Commitments do not match");
+		});
+	}
+
+	#[test]
+	fn split_commitment_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, TallyType::OneCoin, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let parts = [(vote.3[0], 12_u64), (vote.3[1], 8_u64)];
+			let commit_hash = commitment::build_split::<BlakeTwo256, _, _>(1, &2_u64, &SECRET, &parts);
+			assert_ok!(commit(2, 1, commit_hash));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal_split(2, 1, &parts, Some(SECRET)));
+		});
+	}
+
+	#[test]
+	fn prune_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::VoteDeposit<Test>>::put(5);
+			<voting::RetentionPeriod<Test>>::put(10);
+			let public = get_test_key();
+			let vote = generate_1p1v_commit_reveal_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_eq!(Balances::reserved_balance(&public), 5);
+			assert_eq!(Voting::deposit_of(1), Some(5));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(commit(2, 1, commitment::build::<BlakeTwo256, _>(1, &2_u64, &SECRET, &[vote.3[0]])));
+			assert_ok!(commit(3, 1, commitment::build::<BlakeTwo256, _>(1, &3_u64, &SECRET, &[vote.3[1]])));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_ok!(reveal(2, 1, vote.3[0], Some(SECRET)));
+			assert_err!(Voting::prune_vote(Origin::signed(7), 1), "Vote has not concluded");
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			assert_eq!(Voting::concluded_at(1), Some(1));
+
+			System::set_block_number(10);
+			assert_err!(Voting::prune_vote(Origin::signed(7), 1), "Vote is still within its retention period");
+			System::set_block_number(11);
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+
+			// The deposit rewards the pruner
+			assert_eq!(Balances::reserved_balance(&public), 0);
+			assert_eq!(Balances::free_balance(&public), 5);
+			assert_eq!(Balances::free_balance(&7), 75);
+			assert_eq!(Voting::vote_of(1), None);
+			assert_eq!(Voting::commitment_of(&(1, 2)), None);
+			assert_eq!(Voting::commitment_of(&(1, 3)), None);
+			assert_eq!(Voting::reveal_of(&(1, 2)), None);
+			assert_eq!(Voting::committers_of(1), vec![]);
+			assert_eq!(Voting::voters_of(1), vec![]);
+			assert_eq!(Voting::deposit_of(1), None);
+			// Only the tally, result and a hash of the voter set are kept
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], 1), (vote.3[1], 0)]));
+			assert_eq!(Voting::result_of(1), Some(VoteResult::Passed));
+			assert_eq!(
+				Voting::voter_set_hash_of(1),
+				Some(BlakeTwo256::hash_of(&(H256::default(), 2_u64)))
+			);
+			assert_err!(Voting::prune_vote(Origin::signed(7), 1), "Vote record does not exist");
+		});
+	}
+
+	#[test]
+	fn managed_vote_should_reject_initiator_calls() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_err!(Voting::set_managed(2), "Vote record does not exist");
+			assert_ok!(Voting::set_managed(1));
+			assert_err!(advance_stage_as_initiator(public, 1), "Vote is managed by another module");
+			assert_err!(
+				Voting::cancel_vote_as_initiator(Origin::signed(public), 1),
+				"Vote is managed by another module"
+			);
+			assert_ok!(Voting::advance_stage(1));
+			assert_ok!(Voting::cancel_vote(1));
+		});
+	}
+
+	#[test]
+	fn prune_vote_should_be_paginated() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_ok!(advance_stage_as_initiator(public, 1));
+			let voters = voting::MAX_PRUNE_ENTRIES as u64 + 10;
+			for who in 100..(100 + voters) {
+				assert_ok!(reveal(who, 1, vote.3[0], None));
+			}
+			assert_ok!(advance_stage_as_initiator(public, 1));
+
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert!(Voting::vote_of(1).is_some());
+			assert_eq!(Voting::voter_count_of(1), 10);
+			assert_eq!(Voting::reveal_of(&(1, 100 + voters - 1)), None);
+			assert_eq!(Voting::reveal_of(&(1, 100)), Some(vec![vote.3[0]]));
+
+			assert_ok!(Voting::prune_vote(Origin::signed(7), 1));
+			assert_eq!(Voting::vote_of(1), None);
+			assert_eq!(Voting::voters_of(1), vec![]);
+			assert_eq!(Voting::tally_of(1), Some(vec![(vote.3[0], voters), (vote.3[1], 0)]));
+		});
+	}
+
+	#[test]
+	fn prune_cancelled_vote_should_work() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			<voting::VoteDeposit<Test>>::put(20);
+			let public = get_test_key();
+			let vote = generate_1p1v_public_binary_vote();
+			assert_err!(
+				create_vote(public, vote.0, vote.1, vote.2, &vote.3),
+				"Not enough currency for vote deposit"
+			);
+			<voting::VoteDeposit<Test>>::put(5);
+			assert_eq!(Ok(1), create_vote(public, vote.0, vote.1, vote.2, &vote.3));
+			assert_err!(Voting::prune_vote(Origin::signed(public), 1), "Vote has not concluded");
+			assert_ok!(Voting::cancel_vote_as_initiator(Origin::signed(public), 1));
+			assert_ok!(Voting::prune_vote(Origin::signed(public), 1));
+			assert_eq!(Balances::free_balance(&public), 10);
+			assert_eq!(Voting::vote_of(1), None);
+			assert_eq!(Voting::tally_of(1), None);
 		});
 	}
 }
@@ -35,18 +35,81 @@ extern crate srml_system as system;
 extern crate edge_delegation as delegation;
 
 use rstd::prelude::*;
-use rstd::result;
-use system::ensure_signed;
+use rstd::{cmp, result};
+use rstd::collections::btree_set::BTreeSet;
+use system::{ensure_signed, ensure_root};
 use runtime_support::{StorageValue, StorageMap};
 use runtime_support::dispatch::Result;
+use runtime_support::traits::{LockableCurrency, LockIdentifier, ReservableCurrency, WithdrawReason, WithdrawReasons};
 use runtime_primitives::traits::Hash;
-use runtime_primitives::traits::{Zero, One};
-use runtime_primitives::traits::{CheckedAdd};
+use runtime_primitives::traits::{Zero, One, Bounded, As};
+use runtime_primitives::traits::{CheckedAdd, CheckedMul, IntegerSquareRoot, SimpleArithmetic};
 use codec::Encode;
 
 /// A potential outcome of a vote, with 2^32 possible options
 pub type VoteOutcome = [u8; 32];
+/// The weight accumulated by each outcome of a vote, in the order of the vote's outcomes
 pub type Tally<Balance> = Option<Vec<(VoteOutcome, Balance)>>;
+/// A human-readable UTF-8 label for a vote outcome
+pub type OutcomeLabel = Vec<u8>;
+
+/// The domain separation prefix of every commitment preimage
+pub const COMMITMENT_PREFIX: &[u8] = b"edgeware/voting/commitment";
+
+/// The preimage a commitment hashes: the commitment prefix followed by the
+/// encoded vote id, account and secret, then the revealed ballot's bytes.
+pub fn commitment_preimage<AccountId: Encode>(
+	vote_id: u64,
+	who: &AccountId,
+	secret: &VoteOutcome,
+	ballot: &[u8]
+) -> Vec<u8> {
+	let mut buf = Vec::new();
+	buf.extend_from_slice(COMMITMENT_PREFIX);
+	buf.extend_from_slice(&vote_id.encode());
+	buf.extend_from_slice(&who.encode());
+	buf.extend_from_slice(&secret.encode());
+	buf.extend_from_slice(ballot);
+	buf
+}
+
+/// The bytes a commitment covers for a ballot: its outcomes concatenated in order
+pub fn ballot_bytes(vote: &[VoteOutcome]) -> Vec<u8> {
+	let mut buf = Vec::new();
+	for outcome in vote.iter() {
+		buf.extend_from_slice(outcome);
+	}
+	buf
+}
+
+/// The bytes a commitment covers for a split vote: each part's outcome
+/// followed by its encoded amount, concatenated in order
+pub fn split_ballot_bytes<Balance: Encode>(parts: &[(VoteOutcome, Balance)]) -> Vec<u8> {
+	let mut buf = Vec::new();
+	for (outcome, amount) in parts.iter() {
+		buf.extend_from_slice(outcome);
+		buf.extend_from_slice(&amount.encode());
+	}
+	buf
+}
+
+/// The longest outcome label in bytes
+pub const MAX_LABEL_LENGTH: usize = 64;
+
+/// Builds the vote outcome for a label as the blake2 hash of its bytes, so
+/// that clients can derive outcomes from labels without a private mapping.
+#[cfg(feature = "std")]
+pub fn outcome_from_label(label: &str) -> VoteOutcome {
+	runtime_io::blake2_256(label.as_bytes())
+}
+
+/// Builds the outcomes and labels to create a vote with from a list of labels
+#[cfg(feature = "std")]
+pub fn outcomes_from_labels(labels: &[&str]) -> (Vec<VoteOutcome>, Vec<OutcomeLabel>) {
+	let outcomes = labels.iter().map(|label| outcome_from_label(label)).collect();
+	let labels = labels.iter().map(|label| label.as_bytes().to_vec()).collect();
+	(outcomes, labels)
+}
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, Copy, Clone, Eq, PartialEq)]
@@ -59,6 +122,8 @@ pub enum VoteStage {
 	Voting,
 	// Completed voting stage, no more votes allowed
 	Completed,
+	// Cancelled before completion, no more votes allowed and no tally
+	Cancelled,
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -66,8 +131,10 @@ pub enum VoteStage {
 pub enum VoteType {
 	// Binary decision vote, i.e. 2 outcomes
 	Binary,
-	// Multi option decision vote, i.e. > 2 possible outcomes
+	// Multi option approval vote, i.e. > 2 possible outcomes of which any may be approved
 	MultiOption,
+	// Ranked choice vote decided by instant-runoff, i.e. > 2 possible outcomes
+	RankedChoice,
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
@@ -77,11 +144,86 @@ pub enum TallyType {
 	OnePerson,
 	// 1 coin 1 vote, i.e. by balances
 	OneCoin,
+	// n votes for n^2 coins, i.e. by purchased votes
+	Quadratic,
+}
+
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq)]
+pub enum VoteThreshold {
+	// More weight for the approving outcome than against it
+	SimpleMajority,
+	// At least numerator / denominator of the counted weight for the approving outcome, e.g. 2/3
+	SuperMajority(u32, u32),
+	// Adaptive quorum biasing: a super majority is needed to approve when turnout is low
+	SuperMajorityApprove,
+	// Adaptive quorum biasing: a super majority is needed to reject when turnout is low
+	SuperMajorityAgainst,
+}
+
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq)]
+pub enum VoteResult {
+	// Turnout met the quorum and the approving outcome met the threshold
+	Passed,
+	// Turnout met the quorum but the approving outcome fell short of the threshold
+	Failed,
+	// Turnout fell short of the quorum
+	NoQuorum,
+}
+
+impl VoteThreshold {
+	/// Whether `approve` weight passes this threshold over `against` weight,
+	/// given the weight that turned out and the weight that could have.
+	pub fn approved<B>(&self, approve: B, against: B, turnout: B, electorate: B) -> bool
+		where B: SimpleArithmetic + IntegerSquareRoot + As<u64> + Copy
+	{
+		let sqrt_turnout = turnout.integer_sqrt();
+		let sqrt_electorate = electorate.integer_sqrt();
+		match *self {
+			VoteThreshold::SimpleMajority => approve > against,
+			VoteThreshold::SuperMajority(numerator, denominator) => {
+				let total = approve + against;
+				!total.is_zero() && !compare_rationals(
+					approve,
+					total,
+					<B as As<u64>>::sa(numerator as u64),
+					<B as As<u64>>::sa(denominator as u64)
+				)
+			},
+			// against / sqrt(turnout) < approve / sqrt(electorate)
+			VoteThreshold::SuperMajorityApprove => !sqrt_turnout.is_zero() &&
+				compare_rationals(against, sqrt_turnout, approve, sqrt_electorate),
+			// against / sqrt(electorate) < approve / sqrt(turnout)
+			VoteThreshold::SuperMajorityAgainst => !sqrt_turnout.is_zero() &&
+				compare_rationals(against, sqrt_electorate, approve, sqrt_turnout),
+		}
+	}
+}
+
+/// Whether `n1 / d1 < n2 / d2`, without multiplying the operands.
+/// Both denominators must be non-zero.
+fn compare_rationals<B: SimpleArithmetic + Copy>(n1: B, d1: B, n2: B, d2: B) -> bool {
+	let q1 = n1 / d1;
+	let q2 = n2 / d2;
+	if q1 != q2 {
+		return q1 < q2;
+	}
+	let r1 = n1 % d1;
+	let r2 = n2 % d2;
+	if r2.is_zero() {
+		return false;
+	}
+	if r1.is_zero() {
+		return true;
+	}
+	// Compare the reciprocals of the remaining fractions
+	compare_rationals(d2, r2, d1, r1)
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, PartialEq)]
-pub struct VoteData<AccountId> {
+pub struct VoteData<AccountId, Balance> {
 	// creator of vote
 	pub initiator: AccountId,
 	// Stage of the vote
@@ -92,26 +234,96 @@ pub struct VoteData<AccountId> {
 	pub tally_type: TallyType,
 	// Flag for commit/reveal voting scheme
 	pub is_commit_reveal: bool,
+	// Minimum weight that must turn out for the vote to count
+	pub quorum: Option<Balance>,
+	// Threshold the approving outcome must meet to pass, simple majority if unset
+	pub threshold: Option<VoteThreshold>,
 }
 
 #[cfg_attr(feature = "std", derive(Debug))]
 #[derive(Encode, Decode, PartialEq)]
-pub struct VoteRecord<AccountId> {
+pub struct Vote<AccountId, Balance> {
+	// Identifier of the vote
+	pub id: u64,
+	// Vote data record
+	pub data: VoteData<AccountId, Balance>,
+	// Vote outcomes
+	pub outcomes: Vec<VoteOutcome>,
+	// Labels of the vote outcomes in the same order, if any
+	pub labels: Vec<OutcomeLabel>,
+}
+
+/// A vote along with all of its commitments and reveals, as returned by
+/// `vote_records`. Commitments and reveals are stored per voter.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, PartialEq)]
+pub struct VoteRecord<AccountId, Balance> {
 	// Identifier of the vote
 	pub id: u64,
 	// Vote commitments
 	pub commitments: Vec<(AccountId, VoteOutcome)>,
-	// Vote reveals
-	pub reveals: Vec<(AccountId, VoteOutcome)>,
+	// Vote reveals, each a ballot of outcomes in order of preference
+	pub reveals: Vec<(AccountId, Vec<VoteOutcome>)>,
 	// Vote data record
-	pub data: VoteData<AccountId>,
+	pub data: VoteData<AccountId, Balance>,
 	// Vote outcomes
 	pub outcomes: Vec<VoteOutcome>,
+	// Labels of the vote outcomes in the same order, if any
+	pub labels: Vec<OutcomeLabel>,
+}
+
+/// The vote data stored before per-voter storage, kept to migrate old votes
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, PartialEq)]
+pub struct LegacyVoteData<AccountId> {
+	pub initiator: AccountId,
+	pub stage: VoteStage,
+	pub vote_type: VoteType,
+	pub tally_type: TallyType,
+	pub is_commit_reveal: bool,
+}
+
+/// The vote record stored before per-voter storage, kept to migrate old votes
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, PartialEq)]
+pub struct LegacyVoteRecord<AccountId> {
+	pub id: u64,
+	pub commitments: Vec<(AccountId, VoteOutcome)>,
+	pub reveals: Vec<(AccountId, VoteOutcome)>,
+	pub data: LegacyVoteData<AccountId>,
+	pub outcomes: Vec<VoteOutcome>,
+}
+
+/// The current version of the module's storage layout
+const STORAGE_VERSION: u32 = 2;
+/// The most voters and committers removed by one call to prune a vote
+pub const MAX_PRUNE_ENTRIES: u32 = 256;
+/// The number of old vote records migrated per block
+const MIGRATION_BATCH: u64 = 20;
+/// The most delegation backlinks followed to collect the delegators of one voter
+pub const MAX_DELEGATION_WALK: u32 = 256;
+/// The most outcomes a ranked choice vote may have, which bounds the number
+/// of distinct rankings instant-runoff reads when the vote completes
+pub const MAX_RANKED_OUTCOMES: usize = 5;
+/// The highest conviction a voter may reveal with, in voting periods locked
+pub const MAX_CONVICTION: u8 = 6;
+
+/// Decides whether an account holds a verified identity, of one of the given
+/// identity types if any are given. Implemented by the runtime on top of an
+/// identity module.
+pub trait IdentityVerifier<AccountId> {
+	fn is_verified(who: &AccountId, identity_types: &[Vec<u8>]) -> bool;
+}
+
+impl<AccountId> IdentityVerifier<AccountId> for () {
+	fn is_verified(_who: &AccountId, _identity_types: &[Vec<u8>]) -> bool { false }
 }
 
 pub trait Trait: balances::Trait + delegation::Trait {
 	/// The overarching event type.
 	type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
+	/// Verifies the identities of voters in identity-gated votes.
+	type IdentityVerifier: IdentityVerifier<Self::AccountId>;
 }
 
 decl_module! {
@@ -120,139 +332,1355 @@ decl_module! {
 
 		/// A function for commit-reveal voting schemes that adds a vote commitment.
 		///
-		/// A vote commitment is formatted using the native hash function. There
-		/// are currently no cryptoeconomic punishments against not revealing the
-		/// commitment.
+		/// A vote commitment is formatted using the native hash function. If the
+		/// vote has a reveal bond, it is reserved from the sender on their first
+		/// commitment and slashed if they never reveal. Committing again before
+		/// the commit stage ends replaces the sender's previous commitment.
 		pub fn commit(origin, vote_id: u64, commit: VoteOutcome) -> Result {
 			let _sender = ensure_signed(origin)?;
-			let mut record = <VoteRecords<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+			let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
 			ensure!(record.data.is_commit_reveal, "Commitments are not configured for this vote");
 			ensure!(record.data.stage == VoteStage::Commit, "Vote is not in commit stage");
-			// TODO: Allow changing of commits before commit stage ends
-			ensure!(!record.commitments.iter().any(|c| &c.0 == &_sender), "Duplicate commits are not allowed");
+			ensure!(Self::is_eligible(vote_id, &_sender), "Voter does not hold a required identity");
 
-			// Add commitment to record
-			record.commitments.push((_sender.clone(), commit));
 			let id = record.id;
-			<VoteRecords<T>>::insert(id, record);
-			Self::deposit_event(RawEvent::VoteCommitted(id, _sender));
+			let key = (id, _sender.clone());
+			if <Commitments<T>>::exists(&key) {
+				// Replace the existing commitment
+				<Commitments<T>>::insert(key, commit);
+				Self::deposit_event(RawEvent::CommitmentChanged(id, _sender));
+			} else {
+				if let Some(bond) = Self::reveal_bond_of(id) {
+					<balances::Module<T>>::reserve(&_sender, bond)
+						.map_err(|_| "Not enough currency for reveal bond")?;
+				}
+				Self::insert_commitment(id, _sender.clone(), commit);
+				Self::deposit_event(RawEvent::VoteCommitted(id, _sender));
+			}
 			Ok(())
 		}
 
 		/// A function that reveals a vote commitment or serves as the general vote function.
 		///
-		/// There are currently no cryptoeconomic incentives for revealing commited votes.
-		pub fn reveal(origin, vote_id: u64, vote: VoteOutcome, secret: Option<VoteOutcome>) -> Result {
+		/// The vote is a ballot of outcomes: binary votes take exactly one outcome,
+		/// multi option votes approve any set of outcomes and ranked choice votes
+		/// list outcomes in order of preference. The commitment covers the
+		/// ballot's outcomes concatenated in order, with approvals sorted ascending.
+		///
+		/// A valid first reveal returns the sender's reveal bond, if any.
+		/// Revealing again before the voting stage ends replaces the sender's
+		/// previous vote, which must still match their commitment if any.
+		pub fn reveal(origin, vote_id: u64, vote: Vec<VoteOutcome>, secret: Option<VoteOutcome>) -> Result {
 			let _sender = ensure_signed(origin)?;
-			let mut record = <VoteRecords<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+			return Self::do_reveal(_sender, vote_id, vote, secret, 0);
+		}
+
+		/// A function that reveals a vote in a one coin vote with conviction.
+		///
+		/// A conviction of `n` multiplies the sender's weight by `n + 1` in
+		/// exchange for locking it for `n` voting periods after the vote
+		/// completes, up to `MAX_CONVICTION`. The voting period is the vote's
+		/// voting stage length if it has stage lengths, and the configured
+		/// `ConvictionPeriod` otherwise. The lock is released by `unlock`.
+		pub fn reveal_with_conviction(
+			origin,
+			vote_id: u64,
+			vote: Vec<VoteOutcome>,
+			secret: Option<VoteOutcome>,
+			conviction: u8
+		) -> Result {
+			let _sender = ensure_signed(origin)?;
+			return Self::do_reveal(_sender, vote_id, vote, secret, conviction);
+		}
+
+		/// A function that reveals a vote splitting the sender's weight across outcomes.
+		///
+		/// Only available for one coin votes that are not ranked choice. Each part
+		/// assigns an amount of the sender's weight to a distinct outcome, and the
+		/// parts may sum to at most the sender's weight. The commitment covers each
+		/// part's outcome followed by its encoded amount, concatenated in order.
+		pub fn reveal_split(
+			origin,
+			vote_id: u64,
+			parts: Vec<(VoteOutcome, T::Balance)>,
+			secret: Option<VoteOutcome>
+		) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
 			ensure!(record.data.stage == VoteStage::Voting, "Vote is not in voting stage");
-			// Check vote is for a valid outcome
-			ensure!(record.outcomes.iter().any(|o| o == &vote), "Vote outcome is not valid");
-			// TODO: Allow changing of votes
-			ensure!(!record.reveals.iter().any(|c| &c.0 == &_sender), "Duplicate votes are not allowed");
+			ensure!(Self::is_eligible(vote_id, &_sender), "Voter does not hold a required identity");
+			ensure!(record.data.tally_type == TallyType::OneCoin, "Split votes require a one coin vote");
+			ensure!(record.data.vote_type != VoteType::RankedChoice, "Split votes are not allowed in ranked choice votes");
+			// Check vote is for valid, distinct outcomes
+			ensure!(!parts.is_empty(), "Vote outcome is not valid");
+			ensure!(parts.iter().all(|p| record.outcomes.iter().any(|o| o == &p.0)), "Vote outcome is not valid");
+			ensure!(
+				!parts.iter().enumerate().any(|(i, p)| parts[..i].iter().any(|q| q.0 == p.0)),
+				"Vote outcomes must be unique"
+			);
 
-			// Ensure voter committed
-			if record.data.is_commit_reveal {
-				ensure!(secret.is_some(), "Secret is invalid");
-				ensure!(record.commitments.iter().any(|c| &c.0 == &_sender), "Sender already committed");
-				let commit: (T::AccountId, VoteOutcome) = record.commitments
-					.iter()
-					.find(|c| &c.0 == &_sender)
-					.unwrap()
-					.clone();
-
-				let mut buf = Vec::new();
-				buf.extend_from_slice(&_sender.encode());
-				buf.extend_from_slice(&secret.unwrap().encode());
-				buf.extend_from_slice(&vote);
-				let hash = T::Hashing::hash_of(&buf);
-				ensure!(hash.encode() == commit.1.encode(), "Commitments do not match");
-			}
+			Self::check_commitment(&record, &_sender, secret, &split_ballot_bytes(&parts))?;
 
+			// Check the parts fit within the voter's weight before fixing it
 			let id = record.id;
-			record.reveals.push((_sender.clone(), vote));
-			<VoteRecords<T>>::insert(id, record);
-			Self::deposit_event(RawEvent::VoteRevealed(id, _sender, vote));
+			let total = parts.iter()
+				.try_fold(T::Balance::zero(), |acc, p| acc.checked_add(&p.1))
+				.ok_or("Split vote exceeds voter weight")?;
+			let weight = Self::weight_snapshot_of(&(id, _sender.clone()))
+				.unwrap_or_else(|| <balances::Module<T>>::free_balance(&_sender));
+			ensure!(total <= weight, "Split vote exceeds voter weight");
+			Self::lock_weight(id, &_sender);
+
+			let vote = parts.iter().map(|p| p.0).collect();
+			<Convictions<T>>::remove(&(id, _sender.clone()));
+			<SplitVotes<T>>::insert((id, _sender.clone()), parts);
+			Self::store_reveal(&record, _sender, vote);
+			Ok(())
+		}
+
+		/// A function that sets how many votes the sender casts in a quadratic vote.
+		///
+		/// The sender reserves the square of `count` in currency, which is burned
+		/// when the vote completes. Calling again adjusts the reservation to the
+		/// new count, until the sender reveals.
+		pub fn purchase_votes(origin, vote_id: u64, count: u32) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+			ensure!(record.data.tally_type == TallyType::Quadratic, "Votes can only be purchased for quadratic votes");
+			ensure!(
+				record.data.stage == VoteStage::Commit || record.data.stage == VoteStage::Voting,
+				"Vote is not in commit or voting stage"
+			);
+			ensure!(Self::is_eligible(vote_id, &_sender), "Voter does not hold a required identity");
+
+			let key = (vote_id, _sender.clone());
+			ensure!(!<Reveals<T>>::exists(&key), "Votes cannot be purchased after revealing");
+			ensure!(!<CountedIn<T>>::exists(&key), "Votes cannot be purchased once delegated and counted");
+			let old_cost = Self::quadratic_cost(Self::purchased_votes_of(&key));
+			let new_cost = Self::quadratic_cost(count);
+			if new_cost > old_cost {
+				<balances::Module<T>>::reserve(&_sender, new_cost - old_cost)
+					.map_err(|_| "Not enough currency to purchase votes")?;
+			} else {
+				<balances::Module<T>>::unreserve(&_sender, old_cost - new_cost);
+			}
+			if !<PurchasedVotes<T>>::exists(&key) {
+				let index = Self::vote_buyer_count_of(vote_id);
+				<VoteBuyers<T>>::insert((vote_id, index), _sender.clone());
+				<VoteBuyerCount<T>>::insert(vote_id, index + 1);
+			}
+			<PurchasedVotes<T>>::insert(key, count);
+			Self::deposit_event(RawEvent::VotesPurchased(vote_id, _sender, count));
 			Ok(())
 		}
 
 		/// A function to advance the vote stage.
+		///
+		/// Votes created with stage lengths can only be started this way, after
+		/// which they advance automatically.
 		pub fn advance_stage_as_initiator(origin, vote_id: u64) -> Result {
 			let _sender = ensure_signed(origin)?;
-			let record = <VoteRecords<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+			let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
 			ensure!(record.data.initiator == _sender, "Invalid advance attempt by non-owner");
+			ensure!(!Self::is_managed(vote_id), "Vote is managed by another module");
+			if <StageLengths<T>>::exists(vote_id) {
+				ensure!(record.data.stage == VoteStage::PreVoting, "Vote stages advance automatically");
+			}
 			return Self::advance_stage(vote_id);
 		}
+
+		/// Migrate votes stored in an older layout, a batch per block.
+		fn on_initialize(_n: T::BlockNumber) {
+			let version = Self::storage_version();
+			if version < STORAGE_VERSION {
+				// Votes created before commitments were bound to their vote
+				// keep accepting the older commitment format
+				if <LegacyCommitmentBound<T>>::get().is_none() {
+					<LegacyCommitmentBound<T>>::put(Self::vote_record_count());
+				}
+				if version < 1 {
+					Self::migrate_vote_records();
+				} else {
+					<StorageVersion<T>>::put(STORAGE_VERSION);
+				}
+			}
+		}
+
+		/// A function that releases the lock on the sender's weight in a vote
+		/// once the vote has completed or been cancelled, and any conviction
+		/// lock has expired. See `unlock_at`.
+		pub fn unlock(origin, vote_id: u64) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let key = (vote_id, _sender.clone());
+			ensure!(<WeightSnapshots<T>>::exists(&key), "No weight locked for this vote");
+			let until = Self::unlock_at(vote_id, &_sender).ok_or("Vote has not concluded")?;
+			ensure!(<system::Module<T>>::block_number() >= until, "Conviction lock has not expired");
+			<balances::Module<T>>::remove_lock(Self::lock_id(vote_id), &_sender);
+			<ConvictionLocks<T>>::remove(&key);
+			<WeightSnapshots<T>>::remove(&key);
+			Ok(())
+		}
+
+		/// A function that prunes a completed or cancelled vote once its
+		/// retention period has passed, up to `MAX_PRUNE_ENTRIES` committers,
+		/// vote buyers and voters per call. The sender of the call that removes
+		/// the vote itself receives the vote's deposit.
+		pub fn prune_vote(origin, vote_id: u64) -> Result {
+			let _sender = ensure_signed(origin)?;
+			return Self::prune(vote_id, &_sender).map(|_| ());
+		}
+
+		/// A function for the initiator to cancel a vote before it completes.
+		pub fn cancel_vote_as_initiator(origin, vote_id: u64) -> Result {
+			let _sender = ensure_signed(origin)?;
+			let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+			ensure!(record.data.initiator == _sender, "Invalid cancel attempt by non-owner");
+			ensure!(!Self::is_managed(vote_id), "Vote is managed by another module");
+			return Self::cancel_vote(vote_id);
+		}
+
+		/// A function for root to cancel any vote before it completes.
+		pub fn force_cancel_vote(origin, vote_id: u64) -> Result {
+			ensure_root(origin)?;
+			return Self::cancel_vote(vote_id);
+		}
+
+		/// Advance every vote scheduled to leave its current stage at this block.
+		fn on_finalize(n: T::BlockNumber) {
+			for (vote_id, stage) in <StageTransitions<T>>::take(n) {
+				// Skip votes that were advanced by other means since being scheduled
+				let in_stage = <Votes<T>>::get(vote_id).map_or(false, |r| r.data.stage == stage);
+				if in_stage {
+					let _ = Self::advance_stage(vote_id);
+				}
+			}
+		}
 	}
 }
 
 impl<T: Trait> Module<T> {
 	/// A helper function for creating a new vote/ballot.
+	///
+	/// If `stage_lengths` is given as (commit length, voting length) in blocks,
+	/// the vote advances through its commit and voting stages automatically
+	/// once it is started.
 	pub fn create_vote(
 		sender: T::AccountId,
 		vote_type: VoteType,
 		is_commit_reveal: bool,
 		tally_type: TallyType,
-		outcomes: Vec<VoteOutcome>
+		outcomes: Vec<VoteOutcome>,
+		labels: Vec<OutcomeLabel>,
+		stage_lengths: Option<(T::BlockNumber, T::BlockNumber)>
 	) -> result::Result<u64, &'static str> {
-		if vote_type == VoteType::Binary { ensure!(outcomes.len() == 2, "Invalid binary outcomes") }
-		if vote_type  == VoteType::MultiOption { ensure!(outcomes.len() > 2, "Invalid multi option outcomes") }
+		Self::validate_vote(vote_type, is_commit_reveal, &outcomes, &labels, stage_lengths)?;
+		// Reserve the deposit paid to whoever prunes the vote once it is retired
+		let deposit = Self::vote_deposit();
+		if !deposit.is_zero() {
+			<balances::Module<T>>::reserve(&sender, deposit).map_err(|_| "Not enough currency for vote deposit")?;
+		}
 
 		let id = Self::vote_record_count() + 1;
-		<VoteRecords<T>>::insert(id, VoteRecord {
+		<Votes<T>>::insert(id, Vote {
 			id: id,
-			commitments: vec![],
-			reveals: vec![],
 			outcomes: outcomes,
+			labels: labels,
 			data: VoteData {
 				initiator: sender.clone(),
 				stage: VoteStage::PreVoting,
 				vote_type: vote_type,
 				tally_type: tally_type,
 				is_commit_reveal: is_commit_reveal,
+				quorum: None,
+				threshold: None,
 			},
 		});
 
+		if let Some(lengths) = stage_lengths {
+			<StageLengths<T>>::insert(id, lengths);
+		}
+		if !deposit.is_zero() {
+			<VoteDeposits<T>>::insert(id, deposit);
+		}
+
 		<VoteRecordCount<T>>::mutate(|i| *i += 1);
 		Self::deposit_event(RawEvent::VoteCreated(id, sender, vote_type));
 		return Ok(id);
 	}
 
+	/// Checks the parameters of a new vote without touching storage, so that
+	/// callers of `create_vote` can reject invalid votes before reserving anything
+	pub fn validate_vote(
+		vote_type: VoteType,
+		is_commit_reveal: bool,
+		outcomes: &[VoteOutcome],
+		labels: &[OutcomeLabel],
+		stage_lengths: Option<(T::BlockNumber, T::BlockNumber)>
+	) -> Result {
+		if vote_type == VoteType::Binary { ensure!(outcomes.len() == 2, "Invalid binary outcomes") }
+		if vote_type  == VoteType::MultiOption { ensure!(outcomes.len() > 2, "Invalid multi option outcomes") }
+		if vote_type == VoteType::RankedChoice {
+			ensure!(outcomes.len() > 2 && outcomes.len() <= MAX_RANKED_OUTCOMES, "Invalid ranked choice outcomes")
+		}
+		if let Some((commit_length, voting_length)) = stage_lengths {
+			// Only commit-reveal votes have a commit stage to schedule
+			ensure!(!is_commit_reveal || !commit_length.is_zero(), "Stage lengths must be positive");
+			ensure!(!voting_length.is_zero(), "Stage lengths must be positive");
+		}
+		ensure!(labels.is_empty() || labels.len() == outcomes.len(), "Outcome labels do not match outcomes");
+		for label in labels.iter() {
+			ensure!(label.len() <= MAX_LABEL_LENGTH, "Outcome label is too long");
+			ensure!(rstd::str::from_utf8(label).is_ok(), "Outcome label is not valid UTF-8");
+		}
+		Ok(())
+	}
+
+	/// The label of an outcome of a vote, if the vote was created with labels
+	pub fn label_of(vote_id: u64, outcome: &VoteOutcome) -> Option<OutcomeLabel> {
+		let vote = Self::vote_of(vote_id)?;
+		let index = vote.outcomes.iter().position(|o| o == outcome)?;
+		vote.labels.get(index).cloned()
+	}
+
 	/// A helper function for advancing the stage of a vote, as a state machine
 	pub fn advance_stage(vote_id: u64) -> Result {
-		let mut record = <VoteRecords<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		let mut record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
 		let curr_stage = record.data.stage;
 		let next_stage = match curr_stage {
 			VoteStage::PreVoting if record.data.is_commit_reveal => VoteStage::Commit,
 			VoteStage::PreVoting | VoteStage::Commit => VoteStage::Voting,
 			VoteStage::Voting => VoteStage::Completed,
 			VoteStage::Completed => return Err("Vote already completed"),
+			VoteStage::Cancelled => return Err("Vote was cancelled"),
 		};
 		record.data.stage = next_stage;
-		<VoteRecords<T>>::insert(record.id, record);
+		if next_stage == VoteStage::Voting {
+			<VotingStartedAt<T>>::insert(vote_id, <system::Module<T>>::block_number());
+		}
+		// Tally the reveals once no more votes can be cast
+		let conclusion = match next_stage {
+			VoteStage::Completed => Some(Self::conclude(&record)),
+			_ => None,
+		};
+		<Votes<T>>::insert(record.id, record);
 		Self::deposit_event(RawEvent::VoteAdvanced(vote_id, curr_stage, next_stage));
+		// Schedule the end of the new stage for votes that advance on their own
+		if let Some((commit_length, voting_length)) = Self::stage_lengths_of(vote_id) {
+			let length = match next_stage {
+				VoteStage::Commit => Some(commit_length),
+				VoteStage::Voting => Some(voting_length),
+				_ => None,
+			};
+			if let Some(length) = length {
+				let end = <system::Module<T>>::block_number() + length;
+				<StageTransitions<T>>::mutate(end, |transitions| transitions.push((vote_id, next_stage)));
+			}
+		}
+		// Locks, bonds and purchased votes are settled per voter later, by
+		// `unlock` and `prune`, so completing a vote takes bounded work
+		if let Some((tally, result)) = conclusion {
+			<ConcludedAt<T>>::insert(vote_id, <system::Module<T>>::block_number());
+			<VoteTallies<T>>::insert(vote_id, Some(tally.clone()));
+			<VoteResults<T>>::insert(vote_id, result);
+			Self::deposit_event(RawEvent::VoteCompleted(vote_id, tally, result));
+		}
+		Ok(())
+	}
+
+	/// Cancels a vote that has not completed without tallying it. Voters may
+	/// then unlock their weight, and pruning the vote returns its outstanding
+	/// reveal bonds and purchased votes.
+	pub fn cancel_vote(vote_id: u64) -> Result {
+		let mut record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		match record.data.stage {
+			VoteStage::Completed => return Err("Vote already completed"),
+			VoteStage::Cancelled => return Err("Vote was cancelled"),
+			_ => (),
+		}
+		record.data.stage = VoteStage::Cancelled;
+		<Votes<T>>::insert(vote_id, record);
+		<ConcludedAt<T>>::insert(vote_id, <system::Module<T>>::block_number());
+		Self::deposit_event(RawEvent::VoteCancelled(vote_id));
+		Ok(())
+	}
+
+	/// Removes a completed or cancelled vote's records from storage once its
+	/// retention period has passed, keeping only its tally, result and a hash
+	/// of its voter set. Conviction locks are left in place until their voters
+	/// unlock them.
+	///
+	/// Removing a committer settles their reveal bond if they never revealed,
+	/// and removing a vote buyer settles their purchased votes. At most
+	/// `MAX_PRUNE_ENTRIES` committers, vote buyers and voters are removed per
+	/// call, last first. Returns whether the vote itself was removed, in which
+	/// case its deposit is paid to `pruner`.
+	pub fn prune(vote_id: u64, pruner: &T::AccountId) -> result::Result<bool, &'static str> {
+		let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		ensure!(
+			record.data.stage == VoteStage::Completed || record.data.stage == VoteStage::Cancelled,
+			"Vote has not concluded"
+		);
+		// Votes that concluded before their conclusion was recorded are retired
+		let concluded = Self::concluded_at(vote_id).unwrap_or_else(Zero::zero);
+		ensure!(
+			<system::Module<T>>::block_number() >= concluded + Self::retention_period(),
+			"Vote is still within its retention period"
+		);
+
+		let now = <system::Module<T>>::block_number();
+		let mut budget = MAX_PRUNE_ENTRIES;
+		// Committers go first, while the reveals that decide their bonds remain
+		let mut committer_count = Self::committer_count_of(vote_id);
+		while committer_count > 0 && budget > 0 {
+			committer_count -= 1;
+			budget -= 1;
+			if let Some(committer) = <Committers<T>>::take(&(vote_id, committer_count)) {
+				Self::settle_reveal_bond(&record, &committer);
+				<Commitments<T>>::remove(&(vote_id, committer));
+			}
+		}
+		<CommitterCount<T>>::insert(vote_id, committer_count);
+		let mut buyer_count = Self::vote_buyer_count_of(vote_id);
+		while buyer_count > 0 && budget > 0 {
+			buyer_count -= 1;
+			budget -= 1;
+			if let Some(buyer) = <VoteBuyers<T>>::take(&(vote_id, buyer_count)) {
+				Self::settle_purchase(&record, &buyer);
+			}
+		}
+		<VoteBuyerCount<T>>::insert(vote_id, buyer_count);
+		let mut voter_count = Self::voter_count_of(vote_id);
+		while voter_count > 0 && budget > 0 {
+			voter_count -= 1;
+			budget -= 1;
+			if let Some(voter) = <Voters<T>>::take(&(vote_id, voter_count)) {
+				let key = (vote_id, voter.clone());
+				let delegators = <CountedDelegators<T>>::take(&key);
+				for delegator in delegators.iter() {
+					Self::release_delegator(vote_id, delegator);
+				}
+				budget = budget.saturating_sub(delegators.len() as u32);
+				// Keep the end of a conviction lock that outlives the vote
+				if let (Some(weight), Some(until)) = (Self::weight_snapshot_of(&key), Self::unlock_at(vote_id, &voter)) {
+					if until > now {
+						<ConvictionLocks<T>>::insert(&key, (weight, until));
+					}
+				}
+				<Reveals<T>>::remove(&key);
+				<SplitVotes<T>>::remove(&key);
+				<Convictions<T>>::remove(&key);
+				<CountedBallots<T>>::remove(&key);
+				<CountedTurnouts<T>>::remove(&key);
+			}
+		}
+		<VoterCount<T>>::insert(vote_id, voter_count);
+		if voter_count > 0 || committer_count > 0 || buyer_count > 0 {
+			return Ok(false);
+		}
+
+		for index in 0..Self::ranked_ballot_count_of(vote_id) {
+			if let Some(ranking) = <RankedBallots<T>>::take(&(vote_id, index)) {
+				<RankedBallotWeights<T>>::remove(&(vote_id, ranking));
+			}
+		}
+		<RankedBallotCount<T>>::remove(vote_id);
+		<VoterCount<T>>::remove(vote_id);
+		<CommitterCount<T>>::remove(vote_id);
+		<VoteBuyerCount<T>>::remove(vote_id);
+		<RunningTallies<T>>::remove(vote_id);
+		<Turnouts<T>>::remove(vote_id);
+		<CountedTotals<T>>::remove(vote_id);
+		<Votes<T>>::remove(vote_id);
+		<RevealBonds<T>>::remove(vote_id);
+		<RunoffRounds<T>>::remove(vote_id);
+		<StageLengths<T>>::remove(vote_id);
+		<VotingStartedAt<T>>::remove(vote_id);
+		<IdentityRequirements<T>>::remove(vote_id);
+		<ConcludedAt<T>>::remove(vote_id);
+		<ManagedVotes<T>>::remove(vote_id);
+
+		if let Some(deposit) = <VoteDeposits<T>>::take(vote_id) {
+			let _ = <balances::Module<T>>::repatriate_reserved(&record.data.initiator, pruner, deposit);
+		}
+		Self::deposit_event(RawEvent::VotePruned(vote_id, pruner.clone()));
+		Ok(true)
+	}
+
+	/// Reveals a ballot for `sender`, see `reveal` and `reveal_with_conviction`
+	fn do_reveal(
+		sender: T::AccountId,
+		vote_id: u64,
+		vote: Vec<VoteOutcome>,
+		secret: Option<VoteOutcome>,
+		conviction: u8
+	) -> Result {
+		let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		ensure!(record.data.stage == VoteStage::Voting, "Vote is not in voting stage");
+		ensure!(Self::is_eligible(vote_id, &sender), "Voter does not hold a required identity");
+		if conviction > 0 {
+			ensure!(record.data.tally_type == TallyType::OneCoin, "Conviction requires a one coin vote");
+			ensure!(conviction <= MAX_CONVICTION, "Conviction is too high");
+			ensure!(!Self::voting_period_of(vote_id).is_zero(), "Conviction requires a voting period");
+		}
+		// Check vote is for valid outcomes
+		ensure!(!vote.is_empty(), "Vote outcome is not valid");
+		ensure!(vote.iter().all(|v| record.outcomes.iter().any(|o| o == v)), "Vote outcome is not valid");
+		match record.data.vote_type {
+			VoteType::Binary => ensure!(vote.len() == 1, "Vote must have exactly one outcome"),
+			VoteType::MultiOption | VoteType::RankedChoice => ensure!(
+				!vote.iter().enumerate().any(|(i, v)| vote[..i].contains(v)),
+				"Vote outcomes must be unique"
+			),
+		}
+		// Approvals form a set, so they are kept in a canonical order
+		let mut vote = vote;
+		if record.data.vote_type == VoteType::MultiOption {
+			vote.sort();
+		}
+
+		// Ensure voter committed
+		Self::check_commitment(&record, &sender, secret, &ballot_bytes(&vote))?;
+
+		let id = record.id;
+		if record.data.tally_type == TallyType::Quadratic {
+			ensure!(Self::purchased_votes_of(&(id, sender.clone())) > 0, "No votes purchased");
+		}
+		// Fix the voter's coin weight until the vote completes
+		if record.data.tally_type == TallyType::OneCoin {
+			Self::lock_weight(id, &sender);
+		}
+		<SplitVotes<T>>::remove(&(id, sender.clone()));
+		if conviction > 0 {
+			<Convictions<T>>::insert((id, sender.clone()), conviction);
+		} else {
+			<Convictions<T>>::remove(&(id, sender.clone()));
+		}
+		Self::store_reveal(&record, sender, vote);
+		Ok(())
+	}
+
+	/// Checks a revealed ballot against the sender's commitment, if the vote is
+	/// commit-reveal. The commitment is the hash of `commitment_preimage`.
+	///
+	/// Votes created before commitments were bound to their vote also accept
+	/// the older `hash_of(sender ++ secret ++ ballot)` commitments.
+	fn check_commitment(
+		record: &Vote<T::AccountId, T::Balance>,
+		sender: &T::AccountId,
+		secret: Option<VoteOutcome>,
+		ballot: &[u8]
+	) -> Result {
+		if !record.data.is_commit_reveal {
+			return Ok(());
+		}
+		let secret = secret.ok_or("Secret is invalid")?;
+		let commit = Self::commitment_of(&(record.id, sender.clone())).ok_or("Sender already committed")?;
+
+		let hash = T::Hashing::hash(&commitment_preimage(record.id, sender, &secret, ballot));
+		if hash.as_ref() == &commit[..] {
+			return Ok(());
+		}
+		ensure!(
+			<LegacyCommitmentBound<T>>::get().map_or(false, |bound| record.id <= bound),
+			"Commitments do not match"
+		);
+		let mut buf = Vec::new();
+		buf.extend_from_slice(&sender.encode());
+		buf.extend_from_slice(&secret.encode());
+		buf.extend_from_slice(ballot);
+		ensure!(T::Hashing::hash_of(&buf).as_ref() == &commit[..], "Commitments do not match");
 		Ok(())
 	}
+
+	/// Stores a revealed ballot, replacing the sender's previous one if any. A
+	/// first reveal returns the sender's reveal bond.
+	///
+	/// The sender's ballot is recounted into the running tally. A first reveal
+	/// first recounts the voter the sender's weight was delegated to, if any,
+	/// since the sender's own vote overrides it.
+	fn store_reveal(record: &Vote<T::AccountId, T::Balance>, sender: T::AccountId, vote: Vec<VoteOutcome>) {
+		let id = record.id;
+		let key = (id, sender.clone());
+		if <Reveals<T>>::exists(&key) {
+			// Replace the existing vote
+			<Reveals<T>>::insert(key, vote.clone());
+			Self::recount(record, &sender);
+			Self::deposit_event(RawEvent::VoteChanged(id, sender, vote));
+		} else {
+			if record.data.is_commit_reveal {
+				if let Some(bond) = Self::reveal_bond_of(id) {
+					<balances::Module<T>>::unreserve(&sender, bond);
+				}
+			}
+			Self::insert_reveal(id, sender.clone(), vote.clone());
+			// Release the sender and their delegators before counting them here
+			let mut previous = BTreeSet::new();
+			previous.extend(Self::counted_in(&key));
+			previous.extend(Self::delegate_voter_of(id, &sender));
+			for delegate in previous.iter() {
+				Self::recount(record, delegate);
+			}
+			Self::recount(record, &sender);
+			Self::deposit_event(RawEvent::VoteRevealed(id, sender, vote));
+		}
+	}
+
+	/// Replaces the weighted ballots credited to a voter in the running tally
+	/// and turnout of a vote with their current ones, counting the delegators
+	/// that now reach the voter and releasing those that no longer do
+	fn recount(record: &Vote<T::AccountId, T::Balance>, voter: &T::AccountId) {
+		let id = record.id;
+		let key = (id, voter.clone());
+		let old_delegators: BTreeSet<_> = <CountedDelegators<T>>::take(&key).into_iter().collect();
+		let new_delegators: Vec<_> = if <Reveals<T>>::exists(&key) {
+			Self::delegators_of(id, voter)
+		} else {
+			vec![]
+		};
+		for delegator in old_delegators.iter().filter(|d| !new_delegators.contains(d)) {
+			Self::release_delegator(id, delegator);
+		}
+		for delegator in new_delegators.iter().filter(|d| !old_delegators.contains(d)) {
+			Self::count_delegator(record, voter, delegator);
+		}
+		if !new_delegators.is_empty() {
+			<CountedDelegators<T>>::insert(&key, new_delegators);
+		}
+
+		let old = <CountedBallots<T>>::take(&key);
+		let old_turnout = <CountedTurnouts<T>>::take(&key);
+		let (new, new_turnout) = Self::weighted_ballots_of(record, voter);
+		let sum = |ballots: &[(Vec<VoteOutcome>, T::Balance)]| {
+			ballots.iter().fold(Zero::zero(), |acc: T::Balance, b| acc + b.1)
+		};
+		// Conviction multiplies the weight tallied, not the weight that turned out
+		<Turnouts<T>>::mutate(id, |turnout| *turnout = *turnout - old_turnout + new_turnout);
+		<CountedTotals<T>>::mutate(id, |total| *total = *total - sum(&old) + sum(&new));
+		if !new_turnout.is_zero() {
+			<CountedTurnouts<T>>::insert(&key, new_turnout);
+		}
+		let mut tally = Self::running_tally_of(record);
+		for (ballot, weight) in old.iter() {
+			for entry in tally.iter_mut().filter(|t| ballot.contains(&t.0)) {
+				entry.1 = entry.1 - *weight;
+			}
+		}
+		for (ballot, weight) in new.iter() {
+			for entry in tally.iter_mut().filter(|t| ballot.contains(&t.0)) {
+				entry.1 = entry.1 + *weight;
+			}
+		}
+		<RunningTallies<T>>::insert(id, tally);
+		// Instant-runoff needs each ranking, so ranked choice votes also
+		// count the weight of every distinct ranking
+		if record.data.vote_type == VoteType::RankedChoice {
+			for (ranking, weight) in old.iter() {
+				<RankedBallotWeights<T>>::mutate(&(id, ranking.clone()), |w| *w = *w - *weight);
+			}
+			for (ranking, weight) in new.iter() {
+				let ranking_key = (id, ranking.clone());
+				if !<RankedBallotWeights<T>>::exists(&ranking_key) {
+					let index = Self::ranked_ballot_count_of(id);
+					<RankedBallots<T>>::insert((id, index), ranking.clone());
+					<RankedBallotCount<T>>::insert(id, index + 1);
+				}
+				<RankedBallotWeights<T>>::mutate(&ranking_key, |w| *w = *w + *weight);
+			}
+		}
+		if !new.is_empty() {
+			<CountedBallots<T>>::insert(key, new);
+		}
+	}
+
+	/// Counts a delegator's weight for `voter`, fixing and locking it for
+	/// one coin votes like a voter's own weight
+	fn count_delegator(record: &Vote<T::AccountId, T::Balance>, voter: &T::AccountId, who: &T::AccountId) {
+		<CountedIn<T>>::insert((record.id, who.clone()), voter.clone());
+		<CountedVotes<T>>::mutate(who, |votes| votes.push(record.id));
+		if record.data.tally_type == TallyType::OneCoin {
+			Self::lock_weight(record.id, who);
+		}
+	}
+
+	/// Stops counting a delegator's weight in a vote. Its snapshot and lock
+	/// are kept, so it counts with the same weight wherever it moves, until
+	/// the delegator unlocks it once the vote concludes.
+	fn release_delegator(vote_id: u64, who: &T::AccountId) {
+		<CountedIn<T>>::remove((vote_id, who.clone()));
+		<CountedVotes<T>>::mutate(who, |votes| votes.retain(|v| *v != vote_id));
+	}
+
+	/// The running tally of a vote, crediting each counted ballot to every
+	/// outcome on it, in the order of the vote's outcomes
+	fn running_tally_of(record: &Vote<T::AccountId, T::Balance>) -> Vec<(VoteOutcome, T::Balance)> {
+		match <RunningTallies<T>>::get(record.id) {
+			ref tally if tally.is_empty() => record.outcomes.iter().map(|o| (*o, Zero::zero())).collect(),
+			tally => tally,
+		}
+	}
+
+	/// The first account along `who`'s delegation path that revealed a vote,
+	/// which is the voter `delegators_of` credits `who`'s weight to
+	fn delegate_voter_of(vote_id: u64, who: &T::AccountId) -> Option<T::AccountId> {
+		let mut visited = BTreeSet::new();
+		let mut current = who.clone();
+		while visited.insert(current.clone()) {
+			current = <delegation::Module<T>>::delegate_of(&current)?;
+			if <Reveals<T>>::exists(&(vote_id, current.clone())) {
+				return Some(current);
+			}
+		}
+		None
+	}
+
+	/// Records a voter's first commitment, indexing them among the committers
+	fn insert_commitment(vote_id: u64, who: T::AccountId, commit: VoteOutcome) {
+		let index = Self::committer_count_of(vote_id);
+		<Committers<T>>::insert((vote_id, index), who.clone());
+		<CommitterCount<T>>::insert(vote_id, index + 1);
+		<Commitments<T>>::insert((vote_id, who), commit);
+	}
+
+	/// Records a voter's first reveal, indexing them among the voters and
+	/// chaining them onto the vote's voter set hash
+	fn insert_reveal(vote_id: u64, who: T::AccountId, vote: Vec<VoteOutcome>) {
+		let voter_set_hash = Self::voter_set_hash_of(vote_id).unwrap_or_default();
+		<VoterSetHashes<T>>::insert(vote_id, T::Hashing::hash_of(&(voter_set_hash, who.clone())));
+		let index = Self::voter_count_of(vote_id);
+		<Voters<T>>::insert((vote_id, index), who.clone());
+		<VoterCount<T>>::insert(vote_id, index + 1);
+		<Reveals<T>>::insert((vote_id, who), vote);
+	}
+
+	/// The accounts that committed to a vote, in order of first commitment
+	pub fn committers_of(vote_id: u64) -> Vec<T::AccountId> {
+		(0..Self::committer_count_of(vote_id))
+			.filter_map(|i| Self::committer_at(&(vote_id, i)))
+			.collect()
+	}
+
+	/// The accounts that revealed a vote, in order of first reveal
+	pub fn voters_of(vote_id: u64) -> Vec<T::AccountId> {
+		(0..Self::voter_count_of(vote_id))
+			.filter_map(|i| Self::voter_at(&(vote_id, i)))
+			.collect()
+	}
+
+	/// Assembles a vote along with all of its commitments and reveals. This
+	/// reads every voter's entries, so it is meant for clients and tests
+	/// rather than for use on chain.
+	pub fn vote_records(vote_id: u64) -> Option<VoteRecord<T::AccountId, T::Balance>> {
+		let vote = Self::vote_of(vote_id)?;
+		let commitments = Self::committers_of(vote_id)
+			.into_iter()
+			.filter_map(|who| Self::commitment_of(&(vote_id, who.clone())).map(|c| (who, c)))
+			.collect();
+		let reveals = Self::voters_of(vote_id)
+			.into_iter()
+			.filter_map(|who| Self::reveal_of(&(vote_id, who.clone())).map(|r| (who, r)))
+			.collect();
+		Some(VoteRecord {
+			id: vote.id,
+			commitments: commitments,
+			reveals: reveals,
+			data: vote.data,
+			outcomes: vote.outcomes,
+			labels: vote.labels,
+		})
+	}
+
+	/// Moves a batch of vote records stored before per-voter storage into the
+	/// per-voter maps, raising the storage version after the last batch.
+	/// Single outcome reveals become single outcome ballots, and votes still
+	/// open are counted into their running tallies.
+	fn migrate_vote_records() {
+		let start = <MigrationCursor<T>>::get().unwrap_or(1);
+		let end = cmp::min(start + MIGRATION_BATCH - 1, Self::vote_record_count());
+		for id in start..=end {
+			let old = match <VoteRecords<T>>::take(id) {
+				Some(old) => old,
+				None => continue,
+			};
+			let record = Vote {
+				id: old.id,
+				outcomes: old.outcomes,
+				labels: vec![],
+				data: VoteData {
+					initiator: old.data.initiator,
+					stage: old.data.stage,
+					vote_type: old.data.vote_type,
+					tally_type: old.data.tally_type,
+					is_commit_reveal: old.data.is_commit_reveal,
+					quorum: None,
+					threshold: None,
+				},
+			};
+			for (who, commit) in old.commitments {
+				Self::insert_commitment(id, who, commit);
+			}
+			let voters: Vec<_> = old.reveals.into_iter().map(|(who, outcome)| {
+				Self::insert_reveal(id, who.clone(), vec![outcome]);
+				who
+			}).collect();
+			if record.data.stage == VoteStage::Voting {
+				for voter in voters.iter() {
+					Self::recount(&record, voter);
+				}
+			}
+			<Votes<T>>::insert(id, record);
+		}
+		if end >= Self::vote_record_count() {
+			<MigrationCursor<T>>::kill();
+			<StorageVersion<T>>::put(STORAGE_VERSION);
+		} else {
+			<MigrationCursor<T>>::put(end + 1);
+		}
+	}
+
+	/// Requires voters of a commit-reveal vote to reserve `bond` when they
+	/// commit. The bond is returned when they reveal, and slashed when the
+	/// vote is pruned if it completed before they did.
+	pub fn set_reveal_bond(vote_id: u64, bond: T::Balance) -> Result {
+		let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		ensure!(record.data.is_commit_reveal, "Reveal bonds require a commit-reveal vote");
+		ensure!(record.data.stage == VoteStage::PreVoting, "Vote is not in prevoting stage");
+		<RevealBonds<T>>::insert(vote_id, bond);
+		Ok(())
+	}
+
+	/// Sets the minimum turnout and the passing threshold of a vote. Turnout
+	/// is the total weight of the voters counted, including delegated weight
+	/// and before conviction.
+	/// Adaptive thresholds need the size of the electorate, which is only
+	/// known for one coin votes.
+	pub fn set_passing_rules(
+		vote_id: u64,
+		quorum: Option<T::Balance>,
+		threshold: Option<VoteThreshold>
+	) -> Result {
+		let mut record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		ensure!(record.data.stage == VoteStage::PreVoting, "Vote is not in prevoting stage");
+		match threshold {
+			Some(VoteThreshold::SuperMajority(numerator, denominator)) => {
+				ensure!(denominator > 0 && numerator <= denominator, "Invalid passing threshold");
+			},
+			Some(VoteThreshold::SuperMajorityApprove) | Some(VoteThreshold::SuperMajorityAgainst) => {
+				ensure!(record.data.tally_type == TallyType::OneCoin, "Adaptive thresholds require a one coin vote");
+			},
+			_ => (),
+		}
+		record.data.quorum = quorum;
+		record.data.threshold = threshold;
+		<Votes<T>>::insert(vote_id, record);
+		Ok(())
+	}
+
+	/// Hands control of a vote's stages to the calling module, so its initiator
+	/// can no longer advance or cancel it directly
+	pub fn set_managed(vote_id: u64) -> Result {
+		ensure!(<Votes<T>>::exists(vote_id), "Vote record does not exist");
+		<ManagedVotes<T>>::insert(vote_id, true);
+		Ok(())
+	}
+
+	/// Restricts a vote to accounts holding a verified identity, of one of
+	/// `identity_types` if any are given. Delegators without one lend no weight.
+	pub fn set_identity_requirement(vote_id: u64, identity_types: Vec<Vec<u8>>) -> Result {
+		let record = <Votes<T>>::get(vote_id).ok_or("Vote record does not exist")?;
+		ensure!(record.data.stage == VoteStage::PreVoting, "Vote is not in prevoting stage");
+		<IdentityRequirements<T>>::insert(vote_id, identity_types);
+		Ok(())
+	}
+
+	/// Whether an account meets the identity requirement of a vote, if any
+	fn is_eligible(vote_id: u64, who: &T::AccountId) -> bool {
+		match Self::identity_requirement_of(vote_id) {
+			Some(identity_types) => T::IdentityVerifier::is_verified(who, &identity_types),
+			None => true,
+		}
+	}
+
+	/// Settles the reveal bond of a committer to a concluded vote who never
+	/// revealed: slashed if the vote completed, returned if it was cancelled
+	fn settle_reveal_bond(record: &Vote<T::AccountId, T::Balance>, who: &T::AccountId) {
+		let bond = match Self::reveal_bond_of(record.id) {
+			Some(bond) => bond,
+			None => return,
+		};
+		if <Reveals<T>>::exists(&(record.id, who.clone())) {
+			return;
+		}
+		if record.data.stage == VoteStage::Completed {
+			let _ = <balances::Module<T>>::slash_reserved(who, bond);
+			Self::deposit_event(RawEvent::RevealBondSlashed(record.id, who.clone(), bond));
+		} else {
+			<balances::Module<T>>::unreserve(who, bond);
+		}
+	}
+
+	/// The currency a voter pays to cast `count` votes in a quadratic vote
+	pub fn quadratic_cost(count: u32) -> T::Balance {
+		let count = count as u64;
+		<T::Balance as As<u64>>::sa(count * count)
+	}
+
+	/// Settles the currency a buyer reserved to purchase votes in a concluded
+	/// quadratic vote: burned if the vote completed, returned if it was cancelled
+	fn settle_purchase(record: &Vote<T::AccountId, T::Balance>, who: &T::AccountId) {
+		let count = <PurchasedVotes<T>>::take(&(record.id, who.clone()));
+		let cost = Self::quadratic_cost(count);
+		if record.data.stage == VoteStage::Completed {
+			let _ = <balances::Module<T>>::slash_reserved(who, cost);
+		} else {
+			<balances::Module<T>>::unreserve(who, cost);
+		}
+	}
+
+	/// The balance lock identifier used to hold voters' weight for a vote,
+	/// which is the vote id's full encoding so that no two votes share one
+	fn lock_id(vote_id: u64) -> LockIdentifier {
+		let mut id = [0u8; 8];
+		id.copy_from_slice(&vote_id.encode());
+		id
+	}
+
+	/// Snapshots a voter's free balance as their weight for a vote and locks it
+	/// until the vote concludes and the voter unlocks it, so the same coins
+	/// cannot be transferred and revealed again from another account. Locked
+	/// coins still pay transaction fees.
+	fn lock_weight(vote_id: u64, who: &T::AccountId) {
+		if <WeightSnapshots<T>>::exists(&(vote_id, who.clone())) {
+			return;
+		}
+		let weight = <balances::Module<T>>::free_balance(who);
+		<balances::Module<T>>::set_lock(
+			Self::lock_id(vote_id),
+			who,
+			weight,
+			T::BlockNumber::max_value(),
+			WithdrawReasons::except(WithdrawReason::TransactionPayment)
+		);
+		<WeightSnapshots<T>>::insert((vote_id, who.clone()), weight);
+	}
+
+	/// The length of a vote's voting period for conviction locks: its voting
+	/// stage length if it has stage lengths, otherwise the `ConvictionPeriod`
+	fn voting_period_of(vote_id: u64) -> T::BlockNumber {
+		Self::stage_lengths_of(vote_id)
+			.map(|(_, voting_length)| voting_length)
+			.unwrap_or_else(Self::conviction_period)
+	}
+
+	/// The block from which a voter may unlock their weight in a vote, or
+	/// `None` while the vote is open. Voters who revealed with conviction in
+	/// a completed vote stay locked until their conviction times the voting
+	/// period has passed since it completed, everyone else from its conclusion.
+	pub fn unlock_at(vote_id: u64, who: &T::AccountId) -> Option<T::BlockNumber> {
+		let key = (vote_id, who.clone());
+		if let Some((_, until)) = Self::conviction_lock_of(&key) {
+			return Some(until);
+		}
+		let record = match <Votes<T>>::get(vote_id) {
+			Some(record) => record,
+			// Pruned votes only keep the conviction locks that outlive them
+			None => return Some(Zero::zero()),
+		};
+		let conviction = match record.data.stage {
+			VoteStage::Completed => Self::conviction_of(&key),
+			VoteStage::Cancelled => 0,
+			_ => return None,
+		};
+		let concluded = Self::concluded_at(vote_id).unwrap_or_else(Zero::zero);
+		Some(concluded + Self::voting_period_of(vote_id) * <T::BlockNumber as As<u64>>::sa(conviction as u64))
+	}
+
+	/// Tallies a vote that no longer accepts votes and judges its result,
+	/// storing the instant-runoff rounds of ranked choice votes.
+	fn conclude(record: &Vote<T::AccountId, T::Balance>) -> (Vec<(VoteOutcome, T::Balance)>, VoteResult) {
+		let tally = if record.data.vote_type == VoteType::RankedChoice {
+			let rounds = Self::instant_runoff(&record.outcomes, &Self::ranked_ballots_of(record.id));
+			let tally = rounds.last().cloned().unwrap_or_default();
+			<RunoffRounds<T>>::insert(record.id, rounds);
+			tally
+		} else {
+			Self::running_tally_of(record)
+		};
+		let result = Self::judge(&record.data, &tally, Self::turnout_of(record.id), Self::counted_total_of(record.id));
+		(tally, result)
+	}
+
+	/// Judges a tally against the vote's quorum and passing threshold.
+	///
+	/// Binary votes approve their first outcome. Other votes approve their
+	/// leading outcome, weighed against the combined weight of the rest, or
+	/// for multi option votes against the weight that did not approve it.
+	/// The electorate of one coin votes is the total issuance, otherwise
+	/// only the turnout is known. The quorum and adaptive thresholds use the
+	/// `turnout` before conviction, while `counted` is the total weight
+	/// tallied after it.
+	fn judge(
+		data: &VoteData<T::AccountId, T::Balance>,
+		tally: &[(VoteOutcome, T::Balance)],
+		turnout: T::Balance,
+		counted: T::Balance
+	) -> VoteResult {
+		if data.quorum.map_or(false, |quorum| turnout < quorum) {
+			return VoteResult::NoQuorum;
+		}
+		let approving = match data.vote_type {
+			VoteType::Binary => 0,
+			_ => tally.iter().enumerate().fold(0, |best, (i, t)| if t.1 > tally[best].1 { i } else { best }),
+		};
+		let approve = tally.get(approving).map_or(Zero::zero(), |t| t.1);
+		let against = match data.vote_type {
+			// Approval ballots may list several outcomes, so a voter approving the
+			// leading outcome must not also count against it
+			VoteType::MultiOption if counted > approve => counted - approve,
+			VoteType::MultiOption => Zero::zero(),
+			_ => tally.iter()
+				.enumerate()
+				.filter(|(i, _)| *i != approving)
+				.fold(Zero::zero(), |acc: T::Balance, (_, t)| acc + t.1),
+		};
+		let electorate = match data.tally_type {
+			TallyType::OneCoin => <balances::Module<T>>::total_issuance(),
+			_ => turnout,
+		};
+		let threshold = data.threshold.unwrap_or(VoteThreshold::SimpleMajority);
+		if threshold.approved(approve, against, turnout, electorate) {
+			VoteResult::Passed
+		} else {
+			VoteResult::Failed
+		}
+	}
+
+	/// The weight each outcome has received from the revealed votes so far.
+	///
+	/// Ranked choice votes report the final round of their instant-runoff,
+	/// all other votes credit the voter's weight to each outcome of their ballot.
+	pub fn compute_tally(record: &Vote<T::AccountId, T::Balance>) -> Vec<(VoteOutcome, T::Balance)> {
+		if record.data.vote_type == VoteType::RankedChoice {
+			return Self::instant_runoff(&record.outcomes, &Self::ranked_ballots_of(record.id)).pop().unwrap_or_default();
+		}
+		Self::running_tally_of(record)
+	}
+
+	/// The weight counted so far for each distinct ranking of a ranked choice
+	/// vote. A vote has at most `MAX_RANKED_OUTCOMES` outcomes, so there are
+	/// few enough rankings for instant-runoff to read on completion.
+	fn ranked_ballots_of(vote_id: u64) -> Vec<(Vec<VoteOutcome>, T::Balance)> {
+		(0..Self::ranked_ballot_count_of(vote_id))
+			.filter_map(|i| Self::ranked_ballot_at(&(vote_id, i)))
+			.map(|ranking| {
+				let weight = Self::ranked_ballot_weight_of(&(vote_id, ranking.clone()));
+				(ranking, weight)
+			})
+			.collect()
+	}
+
+	/// Pairs a voter's revealed ballot with their weight, if they revealed,
+	/// along with the weight they turned out with before conviction.
+	///
+	/// `OnePerson` votes count one unit per voter, `OneCoin` votes count the
+	/// free balance of each voter and `Quadratic` votes count the votes each
+	/// voter purchased. Delegators counted for the voter, see `delegators_of`,
+	/// add their weight, fixed when they were first counted in one coin votes.
+	/// Split votes yield one single outcome ballot per part, with delegated
+	/// weight divided in proportion to the parts.
+	fn weighted_ballots_of(
+		record: &Vote<T::AccountId, T::Balance>,
+		voter: &T::AccountId
+	) -> (Vec<(Vec<VoteOutcome>, T::Balance)>, T::Balance) {
+		let tally_type = record.data.tally_type;
+		let id = record.id;
+		let key = (id, voter.clone());
+		let ballot = match Self::reveal_of(&key) {
+			Some(ballot) => ballot,
+			None => return (vec![], Zero::zero()),
+		};
+		let delegated = Self::counted_delegators_of(&key)
+			.iter()
+			.map(|d| <WeightSnapshots<T>>::get(&(id, d.clone())).unwrap_or_else(|| Self::weight_of(id, d, tally_type)))
+			.fold(Zero::zero(), |acc: T::Balance, weight| acc + weight);
+		match Self::split_vote_of(&key) {
+			Some(parts) => {
+				let total = parts.iter().fold(Zero::zero(), |acc: T::Balance, p| acc + p.1);
+				let ballots: Vec<_> = parts.into_iter()
+					.map(|(outcome, amount)| (vec![outcome], amount + Self::proportion_of(delegated, amount, total)))
+					.collect();
+				let turnout = ballots.iter().fold(Zero::zero(), |acc: T::Balance, b| acc + b.1);
+				(ballots, turnout)
+			},
+			None => {
+				// Direct voters count with the weight fixed when they revealed,
+				// multiplied by their conviction
+				let own_weight = <WeightSnapshots<T>>::get(&key)
+					.unwrap_or_else(|| Self::weight_of(id, voter, tally_type));
+				let multiplier = Self::conviction_of(&key) as u64 + 1;
+				let weight = own_weight * <T::Balance as As<u64>>::sa(multiplier);
+				(vec![(ballot, weight + delegated)], own_weight + delegated)
+			},
+		}
+	}
+
+	/// Computes `amount * part / total`, rounding down
+	fn proportion_of(amount: T::Balance, part: T::Balance, total: T::Balance) -> T::Balance {
+		if total.is_zero() {
+			return Zero::zero();
+		}
+		match amount.checked_mul(&part) {
+			Some(product) => product / total,
+			None => amount / total * part,
+		}
+	}
+
+	/// Runs instant-runoff elimination over weighted ranked ballots, returning
+	/// the weight of every outcome in each round.
+	///
+	/// Each round counts a ballot towards its highest ranked outcome that has
+	/// not been eliminated. Rounds stop once an outcome holds a strict majority
+	/// of the counted weight or a single outcome remains. Otherwise the outcome
+	/// with the least weight is eliminated, ties going to the one listed last.
+	pub fn instant_runoff(
+		outcomes: &[VoteOutcome],
+		ballots: &[(Vec<VoteOutcome>, T::Balance)]
+	) -> Vec<Vec<(VoteOutcome, T::Balance)>> {
+		let mut eliminated: Vec<VoteOutcome> = vec![];
+		let mut rounds = vec![];
+		loop {
+			let mut round: Vec<(VoteOutcome, T::Balance)> = outcomes
+				.iter()
+				.map(|o| (*o, Zero::zero()))
+				.collect();
+			let mut counted: T::Balance = Zero::zero();
+			for (ranking, weight) in ballots.iter() {
+				if let Some(choice) = ranking.iter().find(|o| !eliminated.contains(*o)) {
+					if let Some(entry) = round.iter_mut().find(|t| &t.0 == choice) {
+						entry.1 = entry.1 + *weight;
+						counted = counted + *weight;
+					}
+				}
+			}
+
+			let continuing: Vec<(VoteOutcome, T::Balance)> = round
+				.iter()
+				.filter(|t| !eliminated.contains(&t.0))
+				.cloned()
+				.collect();
+			let has_majority = continuing.iter().any(|t| t.1 + t.1 > counted);
+			rounds.push(round);
+			if has_majority || continuing.len() <= 1 {
+				break;
+			}
+
+			let loser = continuing.iter().fold(None, |lowest: Option<&(VoteOutcome, T::Balance)>, t| match lowest {
+				Some(l) if l.1 < t.1 => Some(l),
+				_ => Some(t),
+			});
+			match loser {
+				Some(l) => eliminated.push(l.0),
+				None => break,
+			}
+		}
+		rounds
+	}
+
+	/// Collects the accounts whose delegated weight flows to `voter` in a vote.
+	///
+	/// Walks the delegation graph backwards from `voter`, stopping at accounts
+	/// that voted directly since their own vote overrides their delegate's.
+	/// Each delegator is collected once, even if it is listed more than once.
+	/// The walk ends after following `MAX_DELEGATION_WALK` backlinks. Accounts
+	/// that fail the vote's identity requirement or are already counted for
+	/// another voter lend no weight, but the walk continues past them.
+	fn delegators_of(vote_id: u64, voter: &T::AccountId) -> Vec<T::AccountId> {
+		let has_voted = |who: &T::AccountId| <Reveals<T>>::exists(&(vote_id, who.clone()));
+		let mut delegators = vec![];
+		let mut visited = BTreeSet::new();
+		visited.insert(voter.clone());
+		let mut frontier = vec![voter.clone()];
+		let mut steps = 0;
+		'walk: while let Some(account) = frontier.pop() {
+			for delegator in <delegation::Module<T>>::delegates_to(&account).unwrap_or_default() {
+				if steps == MAX_DELEGATION_WALK {
+					break 'walk;
+				}
+				steps += 1;
+				// Skip stale backlinks left behind by re-delegations
+				if <delegation::Module<T>>::delegate_of(&delegator).as_ref() != Some(&account) {
+					continue;
+				}
+				if !has_voted(&delegator) && visited.insert(delegator.clone()) {
+					frontier.push(delegator.clone());
+					delegators.push(delegator);
+				}
+			}
+		}
+		delegators.into_iter()
+			.filter(|d| Self::is_eligible(vote_id, d))
+			.filter(|d| Self::counted_in(&(vote_id, d.clone())).map_or(true, |v| &v == voter))
+			.collect()
+	}
+
+	/// The weight a single voter contributes to a tally of the given type
+	fn weight_of(vote_id: u64, who: &T::AccountId, tally_type: TallyType) -> T::Balance {
+		match tally_type {
+			TallyType::OnePerson => T::Balance::one(),
+			TallyType::OneCoin => <balances::Module<T>>::free_balance(who),
+			TallyType::Quadratic => {
+				let count = Self::purchased_votes_of(&(vote_id, who.clone()));
+				<T::Balance as As<u64>>::sa(count as u64)
+			},
+		}
+	}
+}
+
+impl<T: Trait> delegation::OnDelegationChange<T::AccountId> for Module<T> {
+	/// Moves an account's delegated weight in every open vote it is counted
+	/// in from the voter it was counted for to the one it now reaches
+	fn on_delegation_change(who: &T::AccountId) {
+		for vote_id in Self::counted_votes_of(who) {
+			let record = match <Votes<T>>::get(vote_id).filter(|r| r.data.stage == VoteStage::Voting) {
+				Some(record) => record,
+				// Concluded votes no longer move weight
+				None => {
+					<CountedVotes<T>>::mutate(who, |votes| votes.retain(|v| *v != vote_id));
+					continue;
+				},
+			};
+			let old = Self::counted_in(&(vote_id, who.clone()));
+			if let Some(ref voter) = old {
+				Self::recount(&record, voter);
+			}
+			if let Some(voter) = Self::delegate_voter_of(vote_id, who) {
+				if Some(&voter) != old.as_ref() {
+					Self::recount(&record, &voter);
+				}
+			}
+		}
+	}
 }
 
 decl_event!(
-	pub enum Event<T> where <T as system::Trait>::AccountId {
+	pub enum Event<T> where <T as system::Trait>::AccountId,
+							<T as balances::Trait>::Balance {
 		/// new vote (id, creator, type of vote)
 		VoteCreated(u64, AccountId, VoteType),
 		/// vote stage transition (id, old stage, new stage)
 		VoteAdvanced(u64, VoteStage, VoteStage),
 		/// user commits
 		VoteCommitted(u64, AccountId),
+		/// user replaces their commitment
+		CommitmentChanged(u64, AccountId),
 		/// user reveals a vote
-		VoteRevealed(u64, AccountId, VoteOutcome),
+		VoteRevealed(u64, AccountId, Vec<VoteOutcome>),
+		/// user changes their revealed vote
+		VoteChanged(u64, AccountId, Vec<VoteOutcome>),
+		/// user purchases votes in a quadratic vote (id, voter, number of votes)
+		VotesPurchased(u64, AccountId, u32),
+		/// reveal bond slashed from a voter who never revealed (id, voter, bond)
+		RevealBondSlashed(u64, AccountId, Balance),
+		/// vote completed (id, weight of each outcome, result)
+		VoteCompleted(u64, Vec<(VoteOutcome, Balance)>, VoteResult),
+		/// vote cancelled before completion (id)
+		VoteCancelled(u64),
+		/// concluded vote pruned from storage (id, pruner)
+		VotePruned(u64, AccountId),
 	}
 );
 
 decl_storage! {
 	trait Store for Module<T: Trait> as Voting {
-		/// The map of all vote records indexed by id
-		pub VoteRecords get(vote_records): map u64 => Option<VoteRecord<T::AccountId>>;
+		/// The map of all votes indexed by id
+		pub Votes get(vote_of): map u64 => Option<Vote<T::AccountId, T::Balance>>;
 		/// The number of vote records that have been created
 		pub VoteRecordCount get(vote_record_count): u64;
+		/// The commitment of each voter in a commit-reveal vote
+		pub Commitments get(commitment_of): map (u64, T::AccountId) => Option<VoteOutcome>;
+		/// The accounts that committed to each vote, indexed in order of first commitment
+		pub Committers get(committer_at): map (u64, u32) => Option<T::AccountId>;
+		/// The number of accounts that committed to each vote
+		pub CommitterCount get(committer_count_of): map u64 => u32;
+		/// The revealed ballot of each voter
+		pub Reveals get(reveal_of): map (u64, T::AccountId) => Option<Vec<VoteOutcome>>;
+		/// The accounts that revealed each vote, indexed in order of first reveal
+		pub Voters get(voter_at): map (u64, u32) => Option<T::AccountId>;
+		/// The number of accounts that revealed each vote
+		pub VoterCount get(voter_count_of): map u64 => u32;
+		/// Vote records stored before per-voter storage, taken by the migration
+		pub VoteRecords: map u64 => Option<LegacyVoteRecord<T::AccountId>>;
+		/// The version of the storage layout, raised once old data is migrated
+		pub StorageVersion get(storage_version): u32;
+		/// The id of the next vote record the migration moves
+		pub MigrationCursor: Option<u64>;
+		/// The last vote created before commitments were bound to their vote, whose
+		/// voters may still reveal against commitments in the older format
+		pub LegacyCommitmentBound: Option<u64>;
+		/// The bond reserved from each committer of a commit-reveal vote, if any
+		pub RevealBonds get(reveal_bond_of): map u64 => Option<T::Balance>;
+		/// The number of votes each voter purchased in a quadratic vote
+		pub PurchasedVotes get(purchased_votes_of): map (u64, T::AccountId) => u32;
+		/// The accounts that purchased votes in each quadratic vote, indexed in order of first purchase
+		pub VoteBuyers get(vote_buyer_at): map (u64, u32) => Option<T::AccountId>;
+		/// The number of accounts that purchased votes in each quadratic vote
+		pub VoteBuyerCount get(vote_buyer_count_of): map u64 => u32;
+		/// The parts of each split vote, as (outcome, amount of the voter's weight)
+		pub SplitVotes get(split_vote_of): map (u64, T::AccountId) => Option<Vec<(VoteOutcome, T::Balance)>>;
+		/// The weight of each `OneCoin` voter or delegator, fixed and locked when they are first counted
+		pub WeightSnapshots get(weight_snapshot_of): map (u64, T::AccountId) => Option<T::Balance>;
+		/// The weight of every outcome in each instant-runoff round of a completed ranked choice vote
+		pub RunoffRounds get(runoff_rounds_of): map u64 => Vec<Vec<(VoteOutcome, T::Balance)>>;
+		/// The (commit, voting) stage lengths in blocks of votes that advance automatically
+		pub StageLengths get(stage_lengths_of): map u64 => Option<(T::BlockNumber, T::BlockNumber)>;
+		/// The votes scheduled to leave a stage at each block, along with that stage
+		pub StageTransitions get(stage_transitions_at): map T::BlockNumber => Vec<(u64, VoteStage)>;
+		/// The conviction each voter revealed with, if any
+		pub Convictions get(conviction_of): map (u64, T::AccountId) => u8;
+		/// The weight still locked by each conviction voter once their vote is pruned and the block it unlocks at
+		pub ConvictionLocks get(conviction_lock_of): map (u64, T::AccountId) => Option<(T::Balance, T::BlockNumber)>;
+		/// The block at which each vote entered its voting stage
+		pub VotingStartedAt get(voting_started_at): map u64 => Option<T::BlockNumber>;
+		/// The weighted ballots each voter is currently counted with, including delegated weight
+		pub CountedBallots get(counted_ballots_of): map (u64, T::AccountId) => Vec<(Vec<VoteOutcome>, T::Balance)>;
+		/// The delegators whose weight is currently counted for each voter
+		pub CountedDelegators get(counted_delegators_of): map (u64, T::AccountId) => Vec<T::AccountId>;
+		/// The voter each delegator's weight is currently counted for, so it is never counted twice
+		pub CountedIn get(counted_in): map (u64, T::AccountId) => Option<T::AccountId>;
+		/// The open votes each delegator's weight is currently counted in
+		pub CountedVotes get(counted_votes_of): map T::AccountId => Vec<u64>;
+		/// The weight each outcome of a vote has received so far, kept up to date on every reveal
+		pub RunningTallies: map u64 => Vec<(VoteOutcome, T::Balance)>;
+		/// The total weight counted in each vote so far, including delegated weight, before conviction
+		pub Turnouts get(turnout_of): map u64 => T::Balance;
+		/// The weight each voter is currently counted as turning out with, before conviction
+		pub CountedTurnouts: map (u64, T::AccountId) => T::Balance;
+		/// The total weight tallied in each vote so far, after conviction
+		pub CountedTotals get(counted_total_of): map u64 => T::Balance;
+		/// The distinct rankings counted in each ranked choice vote, indexed in order of first count
+		pub RankedBallots get(ranked_ballot_at): map (u64, u32) => Option<Vec<VoteOutcome>>;
+		/// The number of distinct rankings counted in each ranked choice vote
+		pub RankedBallotCount get(ranked_ballot_count_of): map u64 => u32;
+		/// The weight counted so far for each distinct ranking of a ranked choice vote
+		pub RankedBallotWeights get(ranked_ballot_weight_of): map (u64, Vec<VoteOutcome>) => T::Balance;
+		/// The final tally of each completed vote, indexed by id
+		pub VoteTallies get(tally_of): map u64 => Tally<T::Balance>;
+		/// The result of each completed vote under its quorum and passing threshold
+		pub VoteResults get(result_of): map u64 => Option<VoteResult>;
+		/// Whether each vote's stages are controlled by another module rather than its initiator
+		pub ManagedVotes get(is_managed): map u64 => bool;
+		/// The identity types required of voters in identity-gated votes, any verified identity if empty
+		pub IdentityRequirements get(identity_requirement_of): map u64 => Option<Vec<Vec<u8>>>;
+		/// The deposit reserved from the initiator of each new vote, paid to whoever prunes it
+		pub VoteDeposit get(vote_deposit) config(): T::Balance;
+		/// The number of blocks a concluded vote is kept before it may be pruned
+		pub RetentionPeriod get(retention_period) config(): T::BlockNumber;
+		/// The voting period conviction locks are measured in for votes without stage lengths
+		pub ConvictionPeriod get(conviction_period) config(): T::BlockNumber;
+		/// The deposit reserved for each vote that has not been pruned
+		pub VoteDeposits get(deposit_of): map u64 => Option<T::Balance>;
+		/// The block at which each vote completed or was cancelled
+		pub ConcludedAt get(concluded_at): map u64 => Option<T::BlockNumber>;
+		/// The hash chain of the voters of each vote in reveal order, kept once the vote is pruned
+		pub VoterSetHashes get(voter_set_hash_of): map u64 => Option<T::Hash>;
 	}
 }
@@ -48,11 +48,12 @@ extern crate system;
 extern crate timestamp;
 extern crate treasury;
 extern crate finality_tracker;
-extern crate sudo;
 extern crate offchain_primitives;
 
 extern crate node_primitives;
 extern crate consensus_aura;
+#[cfg(test)]
+extern crate sr_io as runtime_io;
 
 use edge_delegation::delegation;
 use edge_governance::governance;
@@ -74,7 +75,7 @@ use runtime_primitives::{ApplyResult, generic, create_runtime_str};
 use runtime_primitives::transaction_validity::TransactionValidity;
 use runtime_primitives::traits::{
 	BlakeTwo256, Block as BlockT, DigestFor, NumberFor, StaticLookup, CurrencyToVoteHandler,
-	AuthorityIdFor,
+	AuthorityIdFor, EnsureOrigin,
 };
 use version::RuntimeVersion;
 use council::{motions as council_motions, voting as council_voting};
@@ -98,7 +99,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("edgeware"),
 	impl_name: create_runtime_str!("edgeware-node"),
 	authoring_version: 3,
-	spec_version: 5,
+	spec_version: 6,
 	impl_version: 5,
 	apis: RUNTIME_API_VERSIONS,
 };
@@ -198,10 +199,32 @@ impl council::motions::Trait for Runtime {
 	type Event = Event;
 }
 
+/// Accepts a council motion backed by at least four members or a passed
+/// governance funding proposal, so token holders can approve treasury spends too
+pub struct EnsureCouncilOrFunding;
+impl EnsureOrigin<Origin> for EnsureCouncilOrFunding {
+	type Success = ();
+	fn ensure_origin(o: Origin) -> rstd::result::Result<(), &'static str> {
+		council_motions::EnsureMembers::<_4>::ensure_origin(o.clone()).map(|_| ())
+			.or_else(|_| governance::EnsureFunding::ensure_origin(o).map(|_| ()))
+	}
+}
+
+/// Accepts a council motion backed by at least two members or a passed
+/// governance action proposal, so token holders can reject treasury spends too
+pub struct EnsureCouncilOrProposal;
+impl EnsureOrigin<Origin> for EnsureCouncilOrProposal {
+	type Success = ();
+	fn ensure_origin(o: Origin) -> rstd::result::Result<(), &'static str> {
+		council_motions::EnsureMembers::<_2>::ensure_origin(o.clone()).map(|_| ())
+			.or_else(|_| governance::EnsureProposal::ensure_origin(o).map(|_| ()))
+	}
+}
+
 impl treasury::Trait for Runtime {
 	type Currency = Balances;
-	type ApproveOrigin = council_motions::EnsureMembers<_4>;
-	type RejectOrigin = council_motions::EnsureMembers<_2>;
+	type ApproveOrigin = EnsureCouncilOrFunding;
+	type RejectOrigin = EnsureCouncilOrProposal;
 	type Event = Event;
 	type MintedForSpending = ();
 	type ProposalRejection = ();
@@ -218,11 +241,6 @@ impl contract::Trait for Runtime {
 	type GasPayment = ();
 }
 
-impl sudo::Trait for Runtime {
-	type Event = Event;
-	type Proposal = Call;
-}
-
 impl grandpa::Trait for Runtime {
 	type SessionKey = AuthorityId;
 	type Log = Log;
@@ -235,15 +253,77 @@ impl finality_tracker::Trait for Runtime {
 
 impl delegation::Trait for Runtime {
 	type Event = Event;
+	type OnDelegationChange = Voting;
+}
+
+/// Lets votes require voters to hold identities verified by the identity module
+pub struct VerifiedIdentities;
+impl voting::IdentityVerifier<AccountId> for VerifiedIdentities {
+	fn is_verified(who: &AccountId, identity_types: &[Vec<u8>]) -> bool {
+		Identity::is_verified(who, identity_types)
+	}
 }
 
 impl voting::Trait for Runtime {
 	type Event = Event;
+	type IdentityVerifier = VerifiedIdentities;
+}
+
+/// Accepts a council motion approved by at least half of the seats of the
+/// current council, however large it is
+pub struct EnsureHalfCouncil;
+impl EnsureOrigin<Origin> for EnsureHalfCouncil {
+	type Success = ();
+	fn ensure_origin(o: Origin) -> rstd::result::Result<(), &'static str> {
+		match Into::<rstd::result::Result<council_motions::Origin, Origin>>::into(o) {
+			Ok(council_motions::Origin::Members(approvals))
+				if approvals as usize * 2 >= CouncilSeats::active_council().len() => Ok(()),
+			_ => Err("bad origin: expected a motion of half the council"),
+		}
+	}
+}
+
+/// Accepts root, a council motion approved by at least half of the council or
+/// a passed governance action proposal
+pub struct EnsureRootHalfCouncilOrProposal;
+impl EnsureOrigin<Origin> for EnsureRootHalfCouncilOrProposal {
+	type Success = ();
+	fn ensure_origin(o: Origin) -> rstd::result::Result<(), &'static str> {
+		system::ensure_root(o.clone())
+			.or_else(|_| EnsureHalfCouncil::ensure_origin(o.clone()))
+			.or_else(|_| governance::EnsureProposal::ensure_origin(o).map(|_| ()))
+	}
 }
 
 impl governance::Trait for Runtime {
 	type Event = Event;
 	type Currency = Balances;
+	type CancelOrigin = EnsureRootHalfCouncilOrProposal;
+	type VetoOrigin = EnsureHalfCouncil;
+	type Origin = Origin;
+	type Proposal = Call;
+	type Funding = TreasuryFunding;
+}
+
+/// Pays passed funding proposals through the treasury. The proposer submits
+/// the spend, reserving the treasury's proposal bond until it is paid out, and
+/// the passed proposal approves it.
+pub struct TreasuryFunding;
+impl governance::FundingHandler<AccountId, Balance> for TreasuryFunding {
+	fn bond(value: Balance) -> Balance {
+		rstd::cmp::max(Treasury::proposal_bond_minimum(), Treasury::proposal_bond() * value)
+	}
+
+	fn fund(
+		proposal_index: u32,
+		proposer: &AccountId,
+		beneficiary: AccountId,
+		value: Balance
+	) -> support::dispatch::Result {
+		let spend_index = Treasury::proposal_count();
+		Treasury::propose_spend(Origin::signed(proposer.clone()), value, Indices::unlookup(beneficiary))?;
+		Treasury::approve_proposal(governance::RawOrigin::Funding(proposal_index).into(), spend_index)
+	}
 }
 
 impl identity::Trait for Runtime {
@@ -274,11 +354,10 @@ construct_runtime!(
 		Grandpa: grandpa::{Module, Call, Storage, Config<T>, Log(), Event<T>},
 		Treasury: treasury,
 		Contract: contract::{Module, Call, Storage, Config<T>, Event<T>},
-		Sudo: sudo,
 		Identity: identity::{Module, Call, Storage, Config<T>, Event<T>},
 		Delegation: delegation::{Module, Call, Storage, Config<T>, Event<T>},
-		Voting: voting::{Module, Call, Storage, Event<T>},
-		Governance: governance::{Module, Call, Storage, Config<T>, Event<T>},
+		Voting: voting::{Module, Call, Storage, Config<T>, Event<T>},
+		Governance: governance::{Module, Call, Storage, Config<T>, Event<T>, Origin},
 	}
 );
 
@@ -403,4 +482,108 @@ impl_runtime_apis! {
 			Consensus::authorities()
 		}
 	}
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use parity_codec::Encode;
+	use runtime_io::with_externalities;
+	use runtime_primitives::traits::{Hash as HashT, OnFinalize};
+	use substrate_primitives::Blake2Hasher;
+	use support::assert_ok;
+	use voting::{TallyType, VoteResult, VoteType};
+	use governance::{ProposalCategory, ProposalStage, RawEvent, YES_VOTE, NO_VOTE};
+
+	fn account(n: u8) -> AccountId {
+		AccountId::from_raw([n; 32])
+	}
+
+	fn proposal_hash(who: &AccountId, proposal: &[u8]) -> Hash {
+		let mut buf = Vec::new();
+		buf.extend_from_slice(&who.encode());
+		buf.extend_from_slice(proposal);
+		BlakeTwo256::hash(&buf[..])
+	}
+
+	fn new_test_ext() -> runtime_io::TestExternalities<Blake2Hasher> {
+		let mut t = system::GenesisConfig::<Runtime>::default().build_storage().unwrap().0;
+		t.extend(
+			balances::GenesisConfig::<Runtime> {
+				balances: vec![(account(1), 100), (account(2), 100), (account(3), 100)],
+				transaction_base_fee: 0,
+				transaction_byte_fee: 0,
+				existential_deposit: 0,
+				transfer_fee: 0,
+				creation_fee: 0,
+				vesting: vec![],
+			}.build_storage().unwrap().0,
+		);
+		t.extend(
+			governance::GenesisConfig::<Runtime> {
+				voting_length: 10000,
+				proposal_creation_bond: 10,
+				action_delay: 2,
+				funding_delay: 2,
+				upgrade_delay: 5,
+				veto_cooldown: 100,
+				seconding_period: 100,
+				seconding_threshold: 0,
+				seconding_deposit: 5,
+				expiry_slash: Permill::from_percent(50),
+				preimage_byte_deposit: 0,
+				execution_quorum: Permill::from_percent(10),
+			}.build_storage().unwrap().0,
+		);
+		t.into()
+	}
+
+	#[test]
+	fn passed_action_proposal_should_take_effect() {
+		with_externalities(&mut new_test_ext(), || {
+			System::set_block_number(1);
+			let signaling: &[u8] = b"A signaling proposal";
+			assert_ok!(Governance::create_proposal(
+				Origin::signed(account(1)),
+				b"Signal".to_vec(),
+				signaling.to_vec(),
+				ProposalCategory::Signaling,
+				vec![],
+				vec![YES_VOTE, NO_VOTE],
+				vec![],
+				VoteType::Binary,
+				TallyType::OneCoin
+			));
+			let signaling_hash = proposal_hash(&account(1), signaling);
+
+			let call = Call::Governance(governance::Call::force_cancel_proposal(signaling_hash));
+			let action: &[u8] = b"Cancel the signaling proposal";
+			assert_ok!(Governance::create_proposal(
+				Origin::signed(account(2)),
+				b"Cancel".to_vec(),
+				action.to_vec(),
+				ProposalCategory::Action,
+				call.encode(),
+				vec![YES_VOTE, NO_VOTE],
+				vec![],
+				VoteType::Binary,
+				TallyType::OneCoin
+			));
+			let action_hash = proposal_hash(&account(2), action);
+			let vote_id = Governance::proposal_of(action_hash).unwrap().vote_id;
+			assert_ok!(Governance::advance_proposal(Origin::signed(account(2)), action_hash));
+			assert_ok!(Voting::reveal(Origin::signed(account(3)), vote_id, vec![YES_VOTE], None));
+
+			System::set_block_number(10002);
+			<Governance as OnFinalize<BlockNumber>>::on_finalize(10002);
+			assert_eq!(Voting::result_of(vote_id), Some(VoteResult::Passed));
+
+			System::set_block_number(10004);
+			<Governance as OnFinalize<BlockNumber>>::on_finalize(10004);
+			assert_eq!(Governance::proposal_of(signaling_hash).unwrap().stage, ProposalStage::Cancelled);
+			assert!(System::events().iter().any(|e| {
+				e.event == Event::governance(RawEvent::ProposalExecuted(action_hash, true))
+			}));
+		});
+	}
+}
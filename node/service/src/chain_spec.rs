@@ -18,8 +18,8 @@ use substrate_primitives::{ed25519, sr25519, Pair, crypto::UncheckedInto};
 use edgeware_primitives::{AccountId, AuthorityId};
 use edgeware_runtime::{ConsensusConfig, CouncilSeatsConfig, CouncilVotingConfig, DemocracyConfig,
 	SessionConfig, StakingConfig, StakerStatus, TimestampConfig, BalancesConfig, TreasuryConfig,
-	SudoConfig, ContractConfig, GrandpaConfig, IndicesConfig, Permill, Perbill,
-	IdentityConfig, GovernanceConfig, DelegationConfig};
+	ContractConfig, GrandpaConfig, IndicesConfig, Permill, Perbill,
+	IdentityConfig, GovernanceConfig, DelegationConfig, VotingConfig};
 pub use edgeware_runtime::GenesisConfig;
 use substrate_service;
 use hex_literal::{hex, hex_impl};
@@ -59,7 +59,6 @@ pub fn edgeware_testnet_config_gensis() -> GenesisConfig {
 
 	testnet_genesis(
 		initial_authorities, // authorities
-		hex!["fcf5ef308894c9686b8302a23416ff57f4f92049b58ed3711a897d4627c56c94"].unchecked_into(), // root key
 		Some(vec![ // endowed accounts
 			hex!["561f4a7512b6c4a0c9708dd60ca76bb85e2f4e35ff0c5d0c5a63b4148c44e476"].unchecked_into()
 		]),
@@ -116,7 +115,6 @@ pub fn get_authority_keys_from_seed(seed: &str) -> (AccountId, AccountId, Author
 /// Helper function to create GenesisConfig for testing
 pub fn testnet_genesis(
 	initial_authorities: Vec<(AccountId, AccountId, AuthorityId)>,
-	root_key: AccountId,
 	endowed_accounts: Option<Vec<AccountId>>,
 	initial_verifiers: Option<Vec<AccountId>>,
 ) -> GenesisConfig {
@@ -242,9 +240,6 @@ pub fn testnet_genesis(
 			block_gas_limit: 10_000_000,
 			current_schedule: Default::default(),
 		}),
-		sudo: Some(SudoConfig {
-			key: root_key,
-		}),
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.2.clone(), 1)).collect(),
 		}),
@@ -256,7 +251,22 @@ pub fn testnet_genesis(
 		governance: Some(GovernanceConfig {
 			voting_length: 604800, // 7 days
 			proposal_creation_bond: 100,
-
+			action_delay: 1 * DAYS,
+			funding_delay: 1 * DAYS,
+			upgrade_delay: 1 * DAYS,
+			veto_cooldown: 7 * DAYS,
+			seconding_period: 7 * DAYS,
+			seconding_threshold: 3,
+			seconding_deposit: 10,
+			expiry_slash: Permill::from_percent(10),
+			preimage_byte_deposit: 1 * CENTS,
+			execution_quorum: Permill::from_percent(10),
+
+		}),
+		voting: Some(VotingConfig {
+			vote_deposit: 1 * DOLLARS,
+			retention_period: 28 * DAYS,
+			conviction_period: 7 * DAYS,
 		}),
 		delegation: Some(DelegationConfig {
 			delegation_depth: 5,
@@ -268,7 +278,6 @@ pub fn testnet_genesis(
 /// Helper function to create GenesisConfig for commonwealth CI testing
 pub fn cwci_testnet_genesis(
 	initial_authorities: Vec<(AccountId, AccountId, AuthorityId)>,
-	root_key: AccountId,
 	endowed_accounts: Option<Vec<AccountId>>,
 	initial_verifiers: Option<Vec<AccountId>>,
 ) -> GenesisConfig {
@@ -394,9 +403,6 @@ pub fn cwci_testnet_genesis(
 			block_gas_limit: 10_000_000,
 			current_schedule: Default::default(),
 		}),
-		sudo: Some(SudoConfig {
-			key: root_key,
-		}),
 		grandpa: Some(GrandpaConfig {
 			authorities: initial_authorities.iter().map(|x| (x.2.clone(), 1)).collect(),
 		}),
@@ -408,7 +414,22 @@ pub fn cwci_testnet_genesis(
 		governance: Some(GovernanceConfig {
 			voting_length: 4,
 			proposal_creation_bond: 100,
-
+			action_delay: 1,
+			funding_delay: 1,
+			upgrade_delay: 1,
+			veto_cooldown: 4,
+			seconding_period: 4,
+			seconding_threshold: 1,
+			seconding_deposit: 10,
+			expiry_slash: Permill::from_percent(10),
+			preimage_byte_deposit: 1 * CENTS,
+			execution_quorum: Permill::from_percent(10),
+
+		}),
+		voting: Some(VotingConfig {
+			vote_deposit: 1 * DOLLARS,
+			retention_period: 4,
+			conviction_period: 4,
 		}),
 		delegation: Some(DelegationConfig {
 			delegation_depth: 5,
@@ -422,7 +443,6 @@ fn development_config_genesis() -> GenesisConfig {
 		vec![
 			get_authority_keys_from_seed("Alice"),
 		],
-		get_account_id_from_seed("Alice"),
 		None,
 		None,
 	)
@@ -439,7 +459,6 @@ fn local_testnet_genesis() -> GenesisConfig {
 			get_authority_keys_from_seed("Alice"),
 			get_authority_keys_from_seed("Bob"),
 		],
-		get_account_id_from_seed("Alice"),
 		None,
 		None,
 	)
@@ -457,7 +476,6 @@ fn cwci_config_genesis() -> GenesisConfig {
 			get_authority_keys_from_seed("Alice"),
 			get_authority_keys_from_seed("Bob"),
 		],
-		get_account_id_from_seed("Alice"),
 		None,
 		None,
 	)